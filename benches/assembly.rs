@@ -0,0 +1,47 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use htmltoadf::{
+    convert_html_str_to_adf_str_single_pass, convert_html_str_to_adf_str_with_options,
+    ConverterOptions,
+};
+
+/**
+ * A large document mixing the elements we see most: nested lists, tables,
+ * styled inline text and code blocks.
+ */
+fn large_document() -> String {
+    let section = r#"
+        <h2>Section</h2>
+        <p>Some <b>bold</b>, <i>italic</i> and <span style="color: #ff0000">colored</span> text with a <a href="https://example.com">link</a>.</p>
+        <ul>
+            <li>First <em>item</em>
+                <ul><li>Nested <code>code</code></li><li>Another</li></ul>
+            </li>
+            <li>Second item</li>
+        </ul>
+        <table>
+            <tr><th>Name</th><th>Value</th></tr>
+            <tr><td>One</td><td style="width: 100px">1</td></tr>
+            <tr><td>Two</td><td><b>2</b></td></tr>
+        </table>
+        <pre><span class="k">let</span> x = 1;</pre>
+        <blockquote><p>A quote</p></blockquote>
+    "#;
+    format!("<div>{}</div>", section.repeat(500))
+}
+
+fn assembly(c: &mut Criterion) {
+    let html = large_document();
+    let options = ConverterOptions::default();
+    let mut group = c.benchmark_group("assembly");
+    group.sample_size(20);
+    group.bench_function("leaves", |b| {
+        b.iter(|| convert_html_str_to_adf_str_with_options(html.clone(), &options))
+    });
+    group.bench_function("single_pass", |b| {
+        b.iter(|| convert_html_str_to_adf_str_single_pass(html.clone(), &options))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, assembly);
+criterion_main!(benches);
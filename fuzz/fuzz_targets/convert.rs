@@ -0,0 +1,22 @@
+#![no_main]
+
+use htmltoadf::{
+    convert_html_str_to_adf_str_single_pass, convert_html_str_to_storage_format, try_convert_html_str_to_adf_str,
+    ConverterOptions, HtmlToAdfError,
+};
+use libfuzzer_sys::fuzz_target;
+
+// Whatever the input, converting it must never panic. The only error expected is a too large document.
+fuzz_target!(|data: &[u8]| {
+    let html = String::from_utf8_lossy(data).to_string();
+    let options = ConverterOptions {
+        max_output_nodes: Some(10_000),
+        ..Default::default()
+    };
+    match try_convert_html_str_to_adf_str(html.clone(), &options) {
+        Ok(_) | Err(HtmlToAdfError::TooLarge { .. }) => {}
+        Err(error) => panic!("{error}"),
+    }
+    convert_html_str_to_adf_str_single_pass(html.clone(), &options);
+    convert_html_str_to_storage_format(html, &options);
+});
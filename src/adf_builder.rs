@@ -1,24 +1,77 @@
+use crate::adf_structure::NODE_MAP;
+use crate::colors::hex_code_for_color_str;
 use crate::extractor;
+use crate::types::adf_content_type::content_type_for_element;
+use crate::types::adf_content_type::transformed_content_type;
 use crate::types::adf_content_type::content_type_for_node_type;
 use crate::types::adf_content_type::is_valid_child_type;
+use crate::types::adf_content_type::panel_type;
+use crate::types::adf_content_type::AdfContentType;
 use crate::types::adf_content_type::AdfMark;
 use crate::types::adf_content_type::AdfMarkAttributes;
+use crate::types::conversion_stats::ConversionStats;
+use crate::types::conversion_warning::ConversionWarning;
+use crate::types::converter_options::CellAccessibilityPolicy;
+use crate::types::converter_options::ConverterOptions;
+use crate::types::converter_options::ListItemValuePolicy;
+use crate::types::converter_options::MarkOrder;
+use crate::types::converter_options::ReversedListPolicy;
+use crate::types::converter_options::TableSummaryPolicy;
 use crate::types::doc_node::DocNode;
+use crate::types::html_to_adf_error::HtmlToAdfError;
 use crate::types::node_list::NodeHandle;
 use crate::types::node_list::NodeList;
+use crate::urls::rewrite_url;
+use crate::warnings;
+use ego_tree::iter::Edge;
+use ego_tree::Tree;
 use regex::Regex;
 use scraper::ElementRef;
 use scraper::Html;
-use serde_json::{Map, Value};
+use scraper::Node;
+use serde_json::{json, Map, Value};
+use std::panic::{self, AssertUnwindSafe};
+use unicode_segmentation::UnicodeSegmentation;
 
-static VALID_EMPTY_TYPES: [&str; 5] = ["hr", "iframe", "img", "br", "td"];
+static VALID_EMPTY_TYPES: [&str; 7] = ["hr", "iframe", "img", "figure", "br", "td", "time"];
+// The canonical order of marks on a text node, see MarkOrder
+static CANONICAL_MARK_ORDER: [&str; 11] = [
+    "link",
+    "textColor",
+    "backgroundColor",
+    "strong",
+    "em",
+    "underline",
+    "strike",
+    "subsup",
+    "code",
+    "annotation",
+    "border",
+];
+// The attributes a mark is invalid without, see strip_empty_marks
+static REQUIRED_MARK_ATTRIBUTES: [(&str, &str); 5] = [
+    ("link", "href"),
+    ("textColor", "color"),
+    ("backgroundColor", "color"),
+    ("subsup", "type"),
+    ("annotation", "id"),
+];
+// Attributes of table cells that ADF has no equivalent for, see CellAccessibilityPolicy
+pub static CELL_ACCESSIBILITY_ATTRIBUTES: [&str; 2] = ["scope", "headers"];
+// Font families that mark text as code, matched anywhere in the family name (e.g. "Courier New")
+static MONOSPACE_FONTS: [&str; 4] = ["monospace", "courier", "consolas", "menlo"];
+
+// The yellow the Atlassian editor highlights with, for a <mark> without a color
+static DEFAULT_HIGHLIGHT_COLOR: &str = "fef445";
+
+// The gray of the ADF text color palette, used for <small> text when muted_small_text is set
+static MUTED_TEXT_COLOR: &str = "#97a0af";
 
 /**
 * The main procedure for our ADF Builder.
 * Allows us to take an HTML string and turn it into a valid ADF string.
 *
 */
-
 /// ```rust
 /// use htmltoadf::convert_html_str_to_adf_str;
 /// use serde_json::json;
@@ -44,415 +97,1337 @@ static VALID_EMPTY_TYPES: [&str; 5] = ["hr", "iframe", "img", "br", "td"];
 /// }).to_string();
 ///assert_eq!(expected, converted);
 /// ```
-
 pub fn convert_html_str_to_adf_str(html: String) -> String {
-    let fragment = Html::parse_fragment(&extractor::esc_hr(html));
-    let leaf_nodes = extractor::extract_leaves(&fragment);
-    let node_list = build_adf_doc(leaf_nodes);
-    node_list.to_json()
+    convert_html_str_to_adf_str_with_options(html, &ConverterOptions::default())
+}
+
+/// Convert an HTML string to an ADF string, using the given options.
+///
+/// ```rust
+/// use htmltoadf::{convert_html_str_to_adf_str_with_options, ConverterOptions};
+///
+/// let options = ConverterOptions {
+///     max_heading_level: 3,
+///     ..Default::default()
+/// };
+/// let converted = convert_html_str_to_adf_str_with_options("<h5>Hello</h5>".to_string(), &options);
+/// assert!(converted.contains(r#""level":3"#));
+/// ```
+pub fn convert_html_str_to_adf_str_with_options(html: String, options: &ConverterOptions) -> String {
+    convert_parsed_html(&extractor::parse_html(html), options)
+}
+
+/// Convert a full HTML page to an ADF string, using the given options.
+///
+/// The HTML is parsed as a document, whether or not it starts with a doctype or `<html>` tag:
+/// everything the parser places in the `<head>` (such as the `<title>`) is left out, and the
+/// `<body>` becomes the doc. See [`convert_fragment`] for snippets of HTML.
+///
+/// ```rust
+/// use htmltoadf::{convert_document, ConverterOptions};
+///
+/// let converted = convert_document("<title>Page</title><p>Body</p>".to_string(), &ConverterOptions::default());
+/// assert!(!converted.contains("Page"));
+/// ```
+pub fn convert_document(html: String, options: &ConverterOptions) -> String {
+    convert_parsed_html(&extractor::parse_html_document(html), options)
+}
+
+/// Convert a snippet of HTML to an ADF string, using the given options.
+///
+/// The HTML is parsed as a fragment, as if it were the content of a `<body>`, whatever it starts with.
+/// Its content, loose text included, is wrapped in a doc. Any `<html>`, `<head>` or `<body>` tags are
+/// ignored, so an element like `<title>` converts along with everything else. See [`convert_document`]
+/// for full HTML pages.
+///
+/// ```rust
+/// use htmltoadf::{convert_fragment, ConverterOptions};
+///
+/// let converted = convert_fragment("<title>Page</title><p>Body</p>".to_string(), &ConverterOptions::default());
+/// assert!(converted.contains("Page"));
+/// ```
+pub fn convert_fragment(html: String, options: &ConverterOptions) -> String {
+    convert_parsed_html(&extractor::parse_html_fragment(html), options)
+}
+
+/**
+ * Convert parsed HTML to an ADF string.
+ */
+fn convert_parsed_html(fragment: &Html, options: &ConverterOptions) -> String {
+    let leaf_nodes = extractor::extract_leaves(fragment, options);
+    let node_list = build_adf_doc(leaf_nodes, options);
+    document_json(&node_list, options)
+}
+
+/// Convert an HTML string to an ADF string, using the given options.
+/// Also returns warnings describing source content that is not represented in the output,
+/// including the content left out of a document cut short by `max_output_nodes`.
+///
+/// ```rust
+/// use htmltoadf::{convert_html_str_to_adf_str_with_warnings, ConverterOptions};
+///
+/// let options = ConverterOptions {
+///     capture_comments: true,
+///     ..Default::default()
+/// };
+/// let (_, warnings) = convert_html_str_to_adf_str_with_warnings("<!-- note --><p>Hi</p>".to_string(), &options);
+/// assert_eq!(warnings[0].message, "note");
+/// ```
+pub fn convert_html_str_to_adf_str_with_warnings(
+    html: String,
+    options: &ConverterOptions,
+) -> (String, Vec<ConversionWarning>) {
+    let fragment = extractor::parse_html(html.clone());
+    let mut warnings = warnings::collect_warnings(&fragment, &html, options);
+    let leaf_nodes = extractor::extract_leaves(&fragment, options);
+    let node_list = build_adf_doc(leaf_nodes, options);
+    warnings.extend(warnings::truncation_warning(&node_list, options));
+    (document_json(&node_list, options), warnings)
+}
+
+/// Convert an HTML string to an ADF string, using the given options.
+/// Also returns counts of the nodes produced, the source elements dropped and the warnings raised.
+///
+/// ```rust
+/// use htmltoadf::{convert_html_str_to_adf_str_with_stats, ConverterOptions};
+///
+/// let (_, stats) = convert_html_str_to_adf_str_with_stats(
+///     "<p>Hi</p><script>track()</script>".to_string(),
+///     &ConverterOptions::default(),
+/// );
+/// assert_eq!(stats.node_count, 3);
+/// assert_eq!(stats.per_type_counts["paragraph"], 1);
+/// assert_eq!(stats.dropped_count, 1);
+/// ```
+pub fn convert_html_str_to_adf_str_with_stats(html: String, options: &ConverterOptions) -> (String, ConversionStats) {
+    let fragment = extractor::parse_html(html.clone());
+    let mut stats = ConversionStats {
+        dropped_count: extractor::count_excluded(&fragment, options),
+        warning_count: warnings::collect_warnings(&fragment, &html, options).len(),
+        ..Default::default()
+    };
+    let leaf_nodes = extractor::extract_leaves(&fragment, options);
+    let node_list = build_adf_doc(leaf_nodes, options);
+    stats.warning_count += usize::from(warnings::truncation_warning(&node_list, options).is_some());
+    stats.count_nodes(&node_list.to_value());
+    (document_json(&node_list, options), stats)
+}
+
+/**
+ * Serialize a converted document, compact or indented as the options ask.
+ * Keys are always emitted in insertion order (serde_json's preserve_order feature),
+ * so the same input always serializes to byte-identical output.
+ */
+pub fn document_json(node_list: &NodeList, options: &ConverterOptions) -> String {
+    let document = document_value(node_list, options);
+    if options.pretty_json {
+        serde_json::to_string_pretty(&document).unwrap_or_default()
+    } else {
+        serde_json::to_string(&document).unwrap_or_default()
+    }
+}
+
+/// Convert an HTML string to an ADF string, using the given options, failing when the
+/// converted document has more nodes than `max_output_nodes` allows.
+///
+/// ```rust
+/// use htmltoadf::{try_convert_html_str_to_adf_str, ConverterOptions, HtmlToAdfError};
+///
+/// let options = ConverterOptions {
+///     max_output_nodes: Some(3),
+///     ..Default::default()
+/// };
+/// assert!(try_convert_html_str_to_adf_str("<p>One</p>".to_string(), &options).is_ok());
+/// assert_eq!(
+///     try_convert_html_str_to_adf_str("<p>One</p><p>Two</p>".to_string(), &options),
+///     Err(HtmlToAdfError::TooLarge { limit: 3 })
+/// );
+/// ```
+pub fn try_convert_html_str_to_adf_str(html: String, options: &ConverterOptions) -> Result<String, HtmlToAdfError> {
+    let node_list = try_build_adf_doc(html, options)?;
+    Ok(document_json(&node_list, options))
+}
+
+/**
+ * A converted document as a JSON value, with the configured ADF version and any top-level metadata.
+ * The metadata follows the content, and cannot replace the version, type or content of the document.
+ */
+pub fn document_value(node_list: &NodeList, options: &ConverterOptions) -> Value {
+    static DOCUMENT_FIELDS: [&str; 3] = ["version", "type", "content"];
+    let mut document = node_list.to_value();
+    document["version"] = json!(options.adf_version);
+    options
+        .doc_metadata
+        .iter()
+        .filter(|(name, _)| !DOCUMENT_FIELDS.contains(&name.as_str()))
+        .for_each(|(name, value)| document[name] = value.clone());
+    document
+}
+
+/**
+ * Convert an HTML string to an ADF document, as a JSON value.
+ */
+pub fn convert_html_str_to_adf_value(html: String, options: &ConverterOptions) -> Value {
+    let fragment = extractor::parse_html(html);
+    let leaf_nodes = extractor::extract_leaves(&fragment, options);
+    document_value(&build_adf_doc(leaf_nodes, options), options)
+}
+
+/**
+ * Convert an HTML string to a node list, failing when it ended up too large.
+ */
+fn try_build_adf_doc(html: String, options: &ConverterOptions) -> Result<NodeList, HtmlToAdfError> {
+    let fragment = extractor::parse_html(html);
+    let leaf_nodes = extractor::extract_leaves(&fragment, options);
+    let node_list = build_adf_doc(leaf_nodes, options);
+    match options.max_output_nodes {
+        Some(limit) if exceeds_node_limit(&node_list, options) => Err(HtmlToAdfError::TooLarge { limit }),
+        _ => Ok(node_list),
+    }
+}
+
+/// Convert a batch of HTML strings into ADF documents, using the default options.
+/// See [`convert_many_with_options`].
+///
+/// ```rust
+/// use htmltoadf::convert_many;
+///
+/// let converted = convert_many(&["<p>One</p>", "<p>Two"]);
+/// assert_eq!(converted[1].as_ref().unwrap()["content"][0]["type"], "paragraph");
+/// ```
+pub fn convert_many(htmls: &[&str]) -> Vec<Result<Value, HtmlToAdfError>> {
+    convert_many_with_options(htmls, &ConverterOptions::default())
+}
+
+/// Convert a batch of HTML strings into ADF documents, using the given options for all of them.
+///
+/// Each input is converted on its own: one that fails to convert (or exceeds `max_output_nodes`)
+/// is reported as an error in its place, and does not stop the rest of the batch.
+///
+/// Malformed HTML is not an error, it is repaired as a browser would and converted like any other.
+/// `HtmlToAdfError::ConversionFailed` only reports an internal panic in the converter, caught so that it
+/// cannot take the rest of the batch down with it. The panic is still printed by the panic hook as usual.
+pub fn convert_many_with_options(
+    htmls: &[&str],
+    options: &ConverterOptions,
+) -> Vec<Result<Value, HtmlToAdfError>> {
+    htmls
+        .iter()
+        .map(|html| {
+            // Conversion only reads the options, so nothing can be left half updated by a panic
+            panic::catch_unwind(AssertUnwindSafe(|| try_build_adf_doc(html.to_string(), options)))
+            .map_err(|error| {
+                let message = error
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| error.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                HtmlToAdfError::ConversionFailed(message)
+            })
+            .and_then(|node_list| node_list.map(|node_list| document_value(&node_list, options)))
+        })
+        .collect()
+}
+
+/// Assemble a list of leaf nodes (as returned by [`extract_leaves`](crate::extract_leaves))
+/// into an ADF document.
+///
+/// This allows leaves to be inspected, filtered or reordered before assembly.
+/// Each leaf keeps a reference to its node within the parsed HTML, so the path from a leaf
+/// to the root can be found by walking `leaf.node.ancestors()`. The parsed `Html` itself is
+/// therefore not passed in. The options are, as they control how each leaf is converted
+/// (heading levels, allowed tags, attribute hooks etc).
+///
+/// Leaves can also be built by hand, using the `scraper` and `ego_tree` crates re-exported
+/// by this crate to name the parsed nodes.
+///
+/// ```rust
+/// use htmltoadf::{assemble, extract_leaves, parse_html, ConverterOptions};
+///
+/// let options = ConverterOptions::default();
+/// let html = parse_html("<p>Keep</p><p>Skip</p>".to_string());
+/// let leaves = extract_leaves(&html, &options)
+///     .into_iter()
+///     .filter(|leaf| leaf.text != "Skip")
+///     .collect();
+/// let adf = assemble(leaves, &options);
+/// assert_eq!(adf["content"].as_array().unwrap().len(), 1);
+/// ```
+pub fn assemble(leaf_nodes: Vec<DocNode>, options: &ConverterOptions) -> Value {
+    document_value(&build_adf_doc(leaf_nodes, options), options)
 }
 
 /**
  * Accept a list of leaf nodes, and turns it into a fully populated NodeList - representing the structure of our ADF Doc.
  */
-fn build_adf_doc(leaf_nodes: Vec<DocNode>) -> NodeList {
+fn build_adf_doc(leaf_nodes: Vec<DocNode>, options: &ConverterOptions) -> NodeList {
     let mut node_list = Default::default();
     let mut current_paragraph_handle: NodeHandle = 0;
 
-    leaf_nodes.iter().for_each(|leaf| {
-        let is_cell_paragraph = leaf.name == "p" && leaf.node.parent()
-            .and_then(|p| ElementRef::wrap(p))
-            .map_or(false, |e| e.value().name() == "td" || e.value().name() == "th");
-
-        if leaf.text.is_empty() && !VALID_EMPTY_TYPES.contains(&leaf.name) && !is_cell_paragraph {
-            return;
+    for leaf in leaf_nodes.iter().filter(|leaf| is_assembled(leaf)) {
+        if exceeds_node_limit(&node_list, options) {
+            break;
         }
-        let content_type = content_type_for_node_type(leaf.name);
-        let (parent, marks) = build_parent_path(leaf, &mut node_list);
-        let mut insertion_point = find_valid_insertion_point(leaf, parent, &mut node_list);
-        let insertion_node = node_list.node(insertion_point);
+        let (parent, marks) = build_parent_path(leaf, &mut node_list, options);
+        assemble_leaf(leaf, parent, marks, &mut node_list, &mut current_paragraph_handle, options);
+    }
+    match leaf_nodes.first() {
+        Some(leaf) => finish_document(leaf.node.tree(), &mut node_list, options),
+        None => fill_empty_document(&mut node_list, options),
+    }
+    node_list
+}
 
-        let attributes = match content_type.attributes {
-            Some(attribute_generator) => attribute_generator(&ElementRef::wrap(leaf.node).unwrap()),
-            _ => vec![],
-        };
+/**
+ * Returns true once the document has more nodes than the options allow, at which point assembly stops.
+ */
+pub fn exceeds_node_limit(node_list: &NodeList, options: &ConverterOptions) -> bool {
+    options.max_output_nodes.is_some_and(|limit| node_list.count > limit)
+}
+
+/**
+ * Returns true if the given leaf contributes to our document. Leaves without text only do
+ * so when they are of a type that is meaningful while empty.
+ */
+pub fn is_assembled(leaf: &DocNode) -> bool {
+    let is_cell_paragraph = leaf.name == "p" && leaf.node.parent()
+        .and_then(ElementRef::wrap)
+        .is_some_and(|e| e.value().name() == "td" || e.value().name() == "th");
+
+    !leaf.text.is_empty() || VALID_EMPTY_TYPES.contains(&leaf.name) || is_cell_paragraph
+}
+
+/**
+ * Insert a single leaf into our document, beneath the given parent node and carrying the given marks.
+ * current_paragraph_handle tracks the paragraph that sibling inline leaves are being collected into.
+ */
+pub fn assemble_leaf(
+    leaf: &DocNode,
+    parent: NodeHandle,
+    marks: Vec<Value>,
+    node_list: &mut NodeList,
+    current_paragraph_handle: &mut NodeHandle,
+    options: &ConverterOptions,
+) {
+    if leaf.name == "caption" {
+        push_table_caption(leaf, parent, marks, node_list, options);
+        return;
+    }
+    // An empty cell may be a header by its position (within <thead>) rather than its tag,
+    // and an image may be an emoji by its attributes
+    let content_type = match ElementRef::wrap(leaf.node) {
+        Some(element) if leaf.name == "td" || leaf.name == "img" || leaf.name == "time" => content_type_for_element(&element),
+        _ => content_type_for_node_type(leaf.name),
+    };
+    // Embeds become cards where a card is permitted, and links to the embedded content elsewhere
+    let embeds_as_card = leaf.name == "iframe" && is_embed_card_placeable(leaf, parent, node_list);
+    // Emoji, dates and hard breaks are inline, placed wherever text is
+    let is_inline = ["emoji", "date", "hardBreak"].contains(&content_type.typename.as_str());
+    let mut insertion_point = if (leaf.name == "iframe" && !embeds_as_card) || is_inline {
+        find_valid_insertion_point_for_type("text", parent, node_list)
+    } else {
+        find_valid_insertion_point(leaf, parent, node_list)
+    };
+    let insertion_node = node_list.node(insertion_point);
+
+    let attributes = match ElementRef::wrap(leaf.node) {
+        Some(element) => node_attributes(content_type, &element, options),
+        _ => vec![],
+    };
+
+    match leaf.name {
+        "img" | "figure" if content_type.typename != "emoji" => {
+            // Check if the content type has a children extractor
+            if let Some(children_extractor) = content_type.children {
+                let element = ElementRef::wrap(leaf.node).unwrap();
+                // Call the children extractor to get attributes and children
+                let (parent_attrs, children_values) = children_extractor(&element);
 
-        match leaf.name {
-            "img" => {
-                // Check if the content type has a children extractor
-                if let Some(children_extractor) = content_type.children {
-                    // Call the children extractor to get attributes and children
-                    let (parent_attrs, children_values) = children_extractor(&ElementRef::wrap(leaf.node).unwrap());
+                // Create the mediaSingle node with the extracted attributes
+                let media_single_handle = node_list.push_anon(
+                    insertion_point,
+                    content_type.typename.to_string(),
+                    "".to_string(),
+                    &parent_attrs,
+                    vec![],
+                );
 
-                    // Create the mediaSingle node with the extracted attributes
-                    let media_single_handle = node_list.push_anon(
+                // The media node describes the image, which a figure holds somewhere within
+                let image = element
+                    .descendants()
+                    .filter_map(ElementRef::wrap)
+                    .find(|image| image.value().name() == "img")
+                    .unwrap_or(element);
+                let figcaption = element
+                    .descendants()
+                    .filter_map(ElementRef::wrap)
+                    .find(|descendant| descendant.value().name() == "figcaption");
+                let media_marks: Vec<Value> = image_link(&image, &element, &marks, options).into_iter().collect();
+                for child_value in children_values {
+                    match figcaption {
+                        // The caption keeps the marks of its inline content
+                        Some(figcaption) if child_value["type"] == "caption" => {
+                            let caption_handle = node_list.push_anon(
+                                media_single_handle,
+                                "caption".to_string(),
+                                "".to_string(),
+                                &[],
+                                vec![],
+                            );
+                            push_inline_content(node_list, caption_handle, &figcaption, marks.clone(), options);
+                        }
+                        _ => push_child_value(node_list, media_single_handle, &child_value, &image, &media_marks, options),
+                    }
+                }
+            } else {
+                // Fallback to the original behavior if no children extractor is defined
+                let media_group_handle = node_list.push_anon(
+                    insertion_point,
+                    content_type.typename.to_string(),
+                    "".to_string(),
+                    &[],
+                    vec![],
+                );
+                node_list.push_anon(
+                    media_group_handle,
+                    "media".to_string(),
+                    "".to_string(),
+                    &attributes,
+                    vec![],
+                );
+            }
+        }
+        "iframe" => {
+            let caption = embed_caption(&ElementRef::wrap(leaf.node).unwrap());
+            if embeds_as_card {
+                node_list.push_anon(
+                    insertion_point,
+                    content_type.typename.to_string(),
+                    "".to_string(),
+                    &attributes,
+                    vec![],
+                );
+                // ADF cards have no title attribute, so any title follows as a caption paragraph
+                if let Some(caption) = caption {
+                    let paragraph_handle = node_list.push_anon(
                         insertion_point,
-                        content_type.typename.to_string(),
+                        "paragraph".to_string(),
                         "".to_string(),
-                        &parent_attrs,
+                        &[],
                         vec![],
                     );
-
-                    // Process each child node
-                    for child_value in children_values {
-                        if let Some(child_obj) = child_value.as_object() {
-                            if let Some(child_type) = child_obj.get("type").and_then(|t| t.as_str()) {
-                                // Extract child attributes
-                                let mut child_attrs = vec![];
-                                if let Some(attrs_obj) = child_obj.get("attrs").and_then(|a| a.as_object()) {
-                                    for (key, value) in attrs_obj {
-                                        child_attrs.push((key.clone(), value.clone()));
-                                    }
-                                }
-
-                                // Create the child node
-                                node_list.push_anon(
-                                    media_single_handle,
-                                    child_type.to_string(),
-                                    "".to_string(),
-                                    &child_attrs,
-                                    vec![],
-                                );
-                            }
-                        }
-                    }
+                    node_list.push_anon(
+                        paragraph_handle,
+                        "text".to_string(),
+                        caption,
+                        &[],
+                        vec![],
+                    );
+                }
+            } else {
+                let accepts_text = insertion_node
+                    .is_some_and(|node| is_valid_child_type(&node.node_type, "text", node.children.len()));
+                let paragraph_handle = if accepts_text {
+                    insertion_point
                 } else {
-                    // Fallback to the original behavior if no children extractor is defined
-                    let media_group_handle = node_list.push_anon(
+                    node_list.push_anon(
                         insertion_point,
-                        content_type.typename.to_string(),
+                        "paragraph".to_string(),
                         "".to_string(),
                         &[],
                         vec![],
+                    )
+                };
+                let mut marks = marks;
+                if let Some(src) = leaf.node.value().as_element().and_then(|element| element.attr("src")) {
+                    insert_adf_mark(
+                        &mut marks,
+                        String::from("link"),
+                        vec![("href".to_string(), rewrite_url(src, options))],
                     );
+                }
+                order_marks(&mut marks, options);
+                node_list.push_anon(
+                    paragraph_handle,
+                    "text".to_string(),
+                    caption.unwrap_or_else(|| "External Content".to_string()),
+                    &[],
+                    marks,
+                );
+            }
+        }
+        "pre" => {
+            let code_block_handle = node_list.push_anon(
+                insertion_point,
+                content_type.typename.to_string(),
+                "".to_string(),
+                &attributes,
+                vec![],
+            );
+            split_text(&leaf.text, options).into_iter().for_each(|text| {
+                node_list.push_anon(code_block_handle, "text".to_string(), text, &[], vec![]);
+            });
+        }
+        "td" => {
+            // A cell without text. If its content already created the cell there is nothing to add.
+            let _ = node_list.push(
+                leaf.node.id(),
+                insertion_point,
+                content_type.typename.to_string(),
+                "".to_string(),
+                attributes,
+                vec![],
+            );
+        }
+        "hr" => {
+            node_list.push_anon(
+                insertion_point,
+                content_type.typename.to_string(),
+                "".to_string(),
+                &[],
+                vec![],
+            );
+        }
+        "p" => {
+            let paragraph_handle = node_list.push_anon(
+                insertion_point,
+                content_type.typename.to_string(),
+                "".to_string(),
+                &[],
+                vec![],
+            );
+            node_list.push_anon(
+                paragraph_handle,
+                "text".to_string(),
+                leaf.text.to_string(),
+                &attributes,
+                marks,
+            );
+        }
+        _ => {
+            // Emoji and dates carry no marks
+            let marks = if content_type.typename == "emoji" || content_type.typename == "date" { vec![] } else { marks };
+            // Text nodes must sometimes be wrapped in a paragraph to be valid.
+            // If we are a text node and our immediate parent only supports a paragraph we wrap this node inside a paragraph
+            // If a sibling node has already wrapped itself in a paragraph, we will try to use the same paragraph
+            if (insertion_node.is_none()
+                || !is_valid_child_type(
+                    &insertion_node.unwrap().node_type,
+                    "text",
+                    0,
+                )
+                || insertion_point == 1)
+                && (
+                    content_type.typename.eq("text") ||
+                    content_type.typename.eq("hardBreak") ||
+                    content_type.typename.eq("emoji") ||
+                    content_type.typename.eq("date")
+                )
+            {
+                let parent_node = node_list.node(parent);
+                if parent_node.is_some()
+                    && is_valid_child_type(&parent_node.unwrap().node_type, "paragraph", 0)
+                {
+                    insertion_point = parent
+                }
+                let insertion_node = node_list.node(insertion_point);
+                *current_paragraph_handle = if *current_paragraph_handle != 0
+                    && insertion_node.is_some()
+                    && *insertion_node.unwrap().children.last().unwrap_or(&0)
+                        == *current_paragraph_handle
+                {
+                    *current_paragraph_handle
+                } else {
                     node_list.push_anon(
-                        media_group_handle,
-                        "media".to_string(),
+                        insertion_point,
+                        "paragraph".to_string(),
                         "".to_string(),
+                        &[],
+                        vec![],
+                    )
+                };
+                if content_type.typename.eq("text") {
+                    push_text(
+                        node_list,
+                        *current_paragraph_handle,
+                        leaf,
+                        &attributes,
+                        marks,
+                        options,
+                    );
+                }
+                else{
+                    node_list.push_anon(
+                        *current_paragraph_handle,
+                        content_type.typename.to_string(),
+                        leaf.text.to_string(),
                         &attributes,
                         vec![],
                     );
                 }
+            } else if insertion_node.is_some()
+                && is_valid_child_type(
+                    &insertion_node.unwrap().node_type,
+                    &content_type.typename,
+                    insertion_node.unwrap().children.len(),
+                )
+            {
+                if !leaf.text.to_string().is_empty() && !content_type.typename.eq("text") {
+                    let parent_handle = node_list.push_anon(
+                        insertion_point,
+                        content_type.typename.to_string(),
+                        "".to_string(),
+                        &attributes,
+                        vec![],
+                    );
+                    if is_valid_child_type(
+                        &content_type.typename.to_string(),
+                        "text",
+                        0,
+                    ) {
+                        node_list.push_anon(
+                            parent_handle,
+                            "text".to_string(),
+                            leaf.text.to_string(),
+                            &[],
+                            marks,
+                        );
+                    } else {
+                        let wrapper_para_handle = node_list.push_anon(
+                            parent_handle,
+                            "paragraph".to_string(),
+                            "".to_string(),
+                            &[],
+                            vec![],
+                        );
+                        node_list.push_anon(
+                            wrapper_para_handle,
+                            "text".to_string(),
+                            leaf.text.to_string(),
+                            &[],
+                            marks,
+                        );
+                    }
+                } else if content_type.typename.eq("text") {
+                    push_text(node_list, insertion_point, leaf, &attributes, marks, options);
+                } else {
+                    node_list.push_anon(
+                        insertion_point,
+                        content_type.typename.to_string(),
+                        leaf.text.to_string(),
+                        &attributes,
+                        marks,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/**
+ * Insert a node generated by a children extractor (as ADF JSON), along with its content.
+ * Media URLs are rewritten, and attribute hooks run for the image a media node describes.
+ */
+fn push_child_value(
+    node_list: &mut NodeList,
+    parent: NodeHandle,
+    value: &Value,
+    image: &ElementRef,
+    media_marks: &[Value],
+    options: &ConverterOptions,
+) {
+    let child_type = match value["type"].as_str() {
+        Some(child_type) => child_type,
+        None => return,
+    };
+    let mut child_attrs: Vec<(String, Value)> = value["attrs"]
+        .as_object()
+        .map(|attrs| attrs.iter().map(|(key, value)| (key.clone(), value.clone())).collect())
+        .unwrap_or_default();
+    let mut marks = vec![];
+    if child_type == "media" {
+        rewrite_url_attribute(&mut child_attrs, options);
+        apply_attribute_hooks(image, &mut child_attrs, options);
+        marks = media_marks.to_vec();
+    }
+    let handle = node_list.push_anon(
+        parent,
+        child_type.to_string(),
+        value["text"].as_str().unwrap_or_default().to_string(),
+        &child_attrs,
+        marks,
+    );
+    if let Some(content) = value["content"].as_array() {
+        content
+            .iter()
+            .for_each(|child| push_child_value(node_list, handle, child, image, media_marks, options));
+    }
+}
+
+/**
+ * The link mark of an image wrapped in an anchor, which ADF places on the media node.
+ * The anchor is outside of the image's leaf (and so among the marks it receives), or within the figure holding it.
+ */
+fn image_link(image: &ElementRef, leaf: &ElementRef, marks: &[Value], options: &ConverterOptions) -> Option<Value> {
+    if let Some(link) = marks.iter().find(|mark| mark["type"] == "link") {
+        return Some(link.clone());
+    }
+    let href = image
+        .ancestors()
+        .take_while(|ancestor| ancestor.id() != leaf.id())
+        .filter_map(ElementRef::wrap)
+        .find(|ancestor| ancestor.value().name() == "a")
+        .and_then(|anchor| anchor.value().attr("href"))
+        .filter(|href| !href.trim().is_empty())?;
+    Some(json!({"type": "link", "attrs": {"href": rewrite_url(href, options)}}))
+}
+
+/**
+ * A table caption becomes a paragraph right before its table (or, where the table itself is not converted,
+ * wherever a paragraph fits).
+ */
+fn push_table_caption(
+    leaf: &DocNode,
+    parent: NodeHandle,
+    marks: Vec<Value>,
+    node_list: &mut NodeList,
+    options: &ConverterOptions,
+) {
+    let table = node_list.node(parent).filter(|node| node.node_type == "table").map(|node| node.parent);
+    let container = match table {
+        Some(table_parent) => table_parent,
+        None => find_valid_insertion_point_for_type("paragraph", parent, node_list),
+    };
+    let paragraph = node_list.push_anon(container, "paragraph".to_string(), "".to_string(), &[], vec![]);
+    push_inline_content(node_list, paragraph, &ElementRef::wrap(leaf.node).unwrap(), marks, options);
+    if table.is_some() {
+        node_list.move_before(paragraph, parent);
+    }
+}
+
+/**
+ * Convert the inline content of an element (its text and line breaks, with the marks of the elements
+ * around them) into children of the given node. Whitespace at either end is trimmed.
+ */
+fn push_inline_content(
+    node_list: &mut NodeList,
+    parent: NodeHandle,
+    element: &ElementRef,
+    marks: Vec<Value>,
+    options: &ConverterOptions,
+) {
+    let mut open_marks = vec![marks];
+    let mut skipped_depth = 0;
+    element.traverse().for_each(|edge| match edge {
+        Edge::Open(node) | Edge::Close(node) if node.id() == element.id() => {}
+        Edge::Open(node) => {
+            if skipped_depth > 0 {
+                skipped_depth += 1;
+                return;
+            }
+            if let Some(child) = ElementRef::wrap(node) {
+                if extractor::excludes_subtree(&child, options) {
+                    skipped_depth = 1;
+                    return;
+                }
+                let mut marks = open_marks.last().cloned().unwrap_or_default();
+                if !extractor::is_unwrapped(&child, options) {
+                    push_element_marks(&mut marks, &transformed_content_type(&child, options), &child, options);
+                }
+                open_marks.push(marks);
+            }
+        }
+        Edge::Close(node) => {
+            if skipped_depth > 0 {
+                skipped_depth -= 1;
+                return;
+            }
+            if node.value().is_element() {
+                open_marks.pop();
+            }
+            match extractor::leaf_for_node(node, false, options) {
+                Some(leaf) if leaf.name == "text" => {
+                    let mut marks = open_marks.last().cloned().unwrap_or_default();
+                    remove_illegal_marks(&mut marks);
+                    push_text(node_list, parent, &leaf, &[], marks, options);
+                }
+                Some(leaf) if leaf.name == "br" => {
+                    node_list.push_anon(parent, "hardBreak".to_string(), "".to_string(), &[], vec![]);
+                }
+                _ => {}
+            }
+        }
+    });
+    trim_edge_text(node_list, parent, true);
+    trim_edge_text(node_list, parent, false);
+}
+
+/**
+ * An embed becomes a card when it has a source, and its parent permits cards.
+ */
+fn is_embed_card_placeable(leaf: &DocNode, parent: NodeHandle, node_list: &NodeList) -> bool {
+    leaf.node.value().as_element().and_then(|element| element.attr("src")).is_some()
+        && node_list
+            .node(parent)
+            .is_some_and(|node| is_valid_child_type(&node.node_type, "embedCard", node.children.len()))
+}
+
+/**
+ * A human readable name for an embed, taken from its title (or failing that its aria-label).
+ */
+fn embed_caption(element: &ElementRef) -> Option<String> {
+    ["title", "aria-label"]
+        .iter()
+        .filter_map(|name| element.value().attr(name))
+        .map(str::trim)
+        .find(|caption| !caption.is_empty())
+        .map(str::to_string)
+}
+
+/**
+ * Push a text leaf into the given parent. Depending on our options the text
+ * may be split into several adjacent text nodes, or lines separated by hard breaks.
+ */
+fn push_text(
+    node_list: &mut NodeList,
+    parent_handle: NodeHandle,
+    leaf: &DocNode,
+    attributes: &[(String, Value)],
+    marks: Vec<Value>,
+    options: &ConverterOptions,
+) {
+    text_segments(leaf, marks, options)
+        .into_iter()
+        .for_each(|(text, mut marks)| {
+            order_marks(&mut marks, options);
+            let is_preformatted = extractor::is_inside_pre(leaf.node);
+            let keeps_line_breaks = options.preserve_line_breaks_in_paragraphs && !is_preformatted;
+            let lines: Vec<&str> = if keeps_line_breaks {
+                text.split('\n').collect()
+            } else {
+                vec![&text]
+            };
+            lines.into_iter().enumerate().for_each(|(index, line)| {
+                if index > 0 {
+                    node_list.push_anon(parent_handle, "hardBreak".to_string(), "".to_string(), &[], vec![]);
+                }
+                if !line.is_empty() {
+                    split_text(line, options).into_iter().for_each(|text| {
+                        let handle = node_list.push_anon(parent_handle, "text".to_string(), text, attributes, marks.clone());
+                        if is_preformatted {
+                            node_list.preformatted.insert(handle);
+                        }
+                    });
+                }
+            });
+        });
+}
+
+/**
+ * Sort marks into our canonical order, unless the source order was asked for.
+ * The sort is stable, so marks of the same type (and unknown marks) keep their relative order.
+ */
+fn order_marks(marks: &mut [Value], options: &ConverterOptions) {
+    if options.mark_order == MarkOrder::Canonical {
+        marks.sort_by_key(|mark| {
+            CANONICAL_MARK_ORDER
+                .iter()
+                .position(|typename| mark["type"] == *typename)
+                .unwrap_or(CANONICAL_MARK_ORDER.len())
+        });
+    }
+}
+
+/**
+ * Split text into chunks of at most max_text_node_len characters, on grapheme boundaries.
+ * A single grapheme longer than the limit is kept whole.
+ */
+fn split_text(text: &str, options: &ConverterOptions) -> Vec<String> {
+    let max_len = match options.max_text_node_len {
+        Some(max_len) if max_len > 0 && text.chars().count() > max_len => max_len,
+        _ => return vec![text.to_string()],
+    };
+    let mut chunks = vec![];
+    let mut chunk = String::new();
+    let mut chunk_len = 0;
+    for grapheme in text.graphemes(true) {
+        let grapheme_len = grapheme.chars().count();
+        if chunk_len > 0 && chunk_len + grapheme_len > max_len {
+            chunks.push(std::mem::take(&mut chunk));
+            chunk_len = 0;
+        }
+        chunk.push_str(grapheme);
+        chunk_len += grapheme_len;
+    }
+    chunks.push(chunk);
+    chunks
+}
+
+/**
+ * Split the text of a leaf into (text, marks) segments.
+ * With markdown_inline_code enabled, `backtick` delimited spans gain a code mark.
+ * Unmatched backticks are left as they are.
+ */
+fn text_segments(
+    leaf: &DocNode,
+    marks: Vec<Value>,
+    options: &ConverterOptions,
+) -> Vec<(String, Vec<Value>)> {
+    lazy_static! {
+        static ref INLINE_CODE: Regex = Regex::new(r"`([^`]+)`").unwrap();
+    }
+    let has_code_mark = marks.iter().any(|mark| mark["type"] == "code");
+    if !options.markdown_inline_code || has_code_mark || extractor::is_inside_pre(leaf.node) {
+        return vec![(leaf.text.to_string(), marks)];
+    }
+
+    let mut segments = vec![];
+    let mut last_end = 0;
+    for captures in INLINE_CODE.captures_iter(&leaf.text) {
+        let code_span = captures.get(0).unwrap();
+        if code_span.start() > last_end {
+            segments.push((leaf.text[last_end..code_span.start()].to_string(), marks.clone()));
+        }
+        let mut code_marks = marks.clone();
+        insert_adf_mark(&mut code_marks, String::from("code"), vec![]);
+        remove_illegal_marks(&mut code_marks);
+        segments.push((captures[1].to_string(), code_marks));
+        last_end = code_span.end();
+    }
+    if last_end < leaf.text.len() || segments.is_empty() {
+        segments.push((leaf.text[last_end..].to_string(), marks));
+    }
+    segments
+}
+
+/**
+ * Adjustments made once all leaves are assembled, which depend on the document as a whole.
+ */
+pub fn finish_document(tree: &Tree<Node>, node_list: &mut NodeList, options: &ConverterOptions) {
+    move_footer_rows_last(tree, node_list);
+    if options.table_summary_policy == TableSummaryPolicy::Paragraph {
+        add_table_summaries(tree, node_list);
+    }
+    if options.reversed_list_policy == ReversedListPolicy::Reverse {
+        reverse_lists(tree, node_list, options);
+    }
+    if !options.keep_adjacent_rules {
+        node_list.collapse_adjacent("rule");
+    }
+    if options.omit_trailing_empty_cells {
+        omit_trailing_empty_cells(node_list);
+    }
+    if options.split_paragraphs_at_double_breaks {
+        split_paragraphs_at_double_breaks(node_list);
+    }
+    trim_blocks(node_list);
+    if options.nbsp_indentation {
+        indent_paragraphs(node_list);
+    }
+    if options.trim_document {
+        trim_document(node_list);
+    }
+    // A cell must hold at least one block, so cells without content get an empty paragraph
+    node_list.fill_empty("tableCell", "paragraph");
+    node_list.fill_empty("tableHeader", "paragraph");
+    fill_empty_document(node_list, options);
+    strip_empty_marks(node_list);
+}
+
+/**
+ * Drop the marks that are missing an attribute they cannot do without, such as a link without an href
+ * (from an `<a>` without one, or one a hook removed). A node left without marks omits the key altogether.
+ */
+fn strip_empty_marks(node_list: &mut NodeList) {
+    node_list.nodes.iter_mut().for_each(|node| {
+        node.marks.retain(|mark| {
+            let mark_type = mark["type"].as_str().unwrap_or_default();
+            REQUIRED_MARK_ATTRIBUTES
+                .iter()
+                .filter(|(required_type, _)| *required_type == mark_type)
+                .all(|(_, attribute)| match &mark["attrs"][attribute] {
+                    Value::String(value) => !value.trim().is_empty(),
+                    Value::Null => false,
+                    _ => true,
+                })
+        });
+    });
+}
+
+/**
+ * Some ADF consumers reject a document without a block, so a document without content gets an empty paragraph.
+ */
+fn fill_empty_document(node_list: &mut NodeList, options: &ConverterOptions) {
+    if !options.empty_document_paragraph {
+        return;
+    }
+    if node_list.nodes.is_empty() {
+        node_list.push_anon(0, "doc".to_string(), "".to_string(), &[], vec![]);
+    }
+    node_list.fill_empty("doc", "paragraph");
+}
+
+/**
+ * Split paragraphs wherever two or more hard breaks follow each other (whitespace between them aside),
+ * dropping those breaks along with the whitespace either side of them.
+ */
+fn split_paragraphs_at_double_breaks(node_list: &mut NodeList) {
+    let mut handle = 1;
+    // Paragraphs split off are appended, so are split in turn when we reach them
+    while handle <= node_list.nodes.len() {
+        if node_list.nodes[handle - 1].node_type == "paragraph" {
+            if let Some((start, end)) = double_break(node_list, handle) {
+                let rest = node_list.split_off(handle, end);
+                node_list.nodes[handle - 1].children.truncate(start);
+                trim_edge_text(node_list, handle, false);
+                trim_edge_text(node_list, rest, true);
+            }
+        }
+        handle += 1;
+    }
+}
+
+/**
+ * The range of children making up the first run of two or more hard breaks within a paragraph.
+ */
+fn double_break(node_list: &NodeList, handle: NodeHandle) -> Option<(usize, usize)> {
+    let children = &node_list.nodes[handle - 1].children;
+    let mut run: Option<(usize, usize, usize)> = None;
+    for (index, child) in children.iter().enumerate() {
+        let child = &node_list.nodes[child - 1];
+        run = match (child.node_type.as_str(), run) {
+            ("hardBreak", Some((start, _, breaks))) => Some((start, index + 1, breaks + 1)),
+            ("hardBreak", None) => Some((index, index + 1, 1)),
+            ("text", Some(run)) if child.text.trim().is_empty() => Some(run),
+            (_, Some((start, end, breaks))) if breaks > 1 => return Some((start, end)),
+            _ => None,
+        };
+    }
+    run.filter(|(_, _, breaks)| *breaks > 1).map(|(start, end, _)| (start, end))
+}
+
+/**
+ * Trim the whitespace from the start of the first (or end of the last) child of a node, when it is text.
+ */
+fn trim_edge_text(node_list: &mut NodeList, handle: NodeHandle, start: bool) {
+    let children = &node_list.nodes[handle - 1].children;
+    if let Some(child) = if start { children.first() } else { children.last() }.copied() {
+        let child = &mut node_list.nodes[child - 1];
+        if child.node_type == "text" {
+            child.text = if start { child.text.trim_start() } else { child.text.trim_end() }.to_string();
+        }
+    }
+}
+
+/**
+ * Trim the whitespace at the start and end of every paragraph and heading, as a browser renders a block.
+ * Only ASCII whitespace is trimmed, so non-breaking spaces are kept, as are the whitespace between inline nodes
+ * and the whitespace within a <pre>.
+ * A block holding nothing but whitespace is left as it is, as it is usually a spacer (such as <p>&nbsp;</p>).
+ */
+fn trim_blocks(node_list: &mut NodeList) {
+    for handle in 1..=node_list.nodes.len() {
+        let node = &node_list.nodes[handle - 1];
+        if node.node_type != "paragraph" && node.node_type != "heading" {
+            continue;
+        }
+        let children = node.children.clone();
+        let is_blank = children.iter().all(|child| {
+            let child = &node_list.nodes[child - 1];
+            child.node_type == "text" && child.text.trim().is_empty()
+        });
+        if !is_blank {
+            trim_block_edge(node_list, children.iter(), true);
+            trim_block_edge(node_list, children.iter().rev(), false);
+        }
+    }
+}
+
+/**
+ * Trim the text nodes at one edge of a block, moving inwards past any that are left empty.
+ */
+fn trim_block_edge<'a>(node_list: &mut NodeList, children: impl Iterator<Item = &'a NodeHandle>, start: bool) {
+    for child in children {
+        if node_list.preformatted.contains(child) {
+            return;
+        }
+        let node = &mut node_list.nodes[child - 1];
+        if node.node_type != "text" {
+            return;
+        }
+        let is_trimmed = |c: char| c.is_ascii_whitespace();
+        node.text = if start { node.text.trim_start_matches(is_trimmed) } else { node.text.trim_end_matches(is_trimmed) }
+            .to_string();
+        if !node.text.is_empty() {
+            return;
+        }
+    }
+}
+
+/**
+ * Indent the paragraphs of the document that start with a run of non-breaking spaces, a level for every four of them.
+ * The spaces (and any other whitespace mixed in with them) are removed, unless nothing would be left of the text.
+ * ADF only allows indentation on paragraphs at the top level of the document.
+ */
+fn indent_paragraphs(node_list: &mut NodeList) {
+    let paragraphs = match node_list.node(1) {
+        Some(doc) => doc.children.clone(),
+        None => return,
+    };
+    for paragraph in paragraphs {
+        let first = match node_list.node(paragraph) {
+            Some(node) if node.node_type == "paragraph" => node.children.first().copied(),
+            _ => continue,
+        };
+        let text = match first.and_then(|first| node_list.node_mut(first)) {
+            Some(text) if text.node_type == "text" => text,
+            _ => continue,
+        };
+        let indented = text.text.trim_start_matches(|c: char| c.is_whitespace());
+        let spaces = text.text[..text.text.len() - indented.len()].matches('\u{a0}').count();
+        let level = (spaces / 4).min(6);
+        if level == 0 || indented.is_empty() {
+            continue;
+        }
+        text.text = indented.to_string();
+        if let Some(node) = node_list.node_mut(paragraph) {
+            node.marks.push(json!({"type": "indentation", "attrs": {"level": level}}));
+        }
+    }
+}
+
+/**
+ * Remove the blank paragraphs, holding nothing but whitespace and line breaks, from the start and end of the document.
+ */
+fn trim_document(node_list: &mut NodeList) {
+    let is_blank = |node_list: &NodeList, handle: NodeHandle| {
+        node_list.node(handle).is_some_and(|node| {
+            node.node_type == "paragraph"
+                && node.children.iter().filter_map(|child| node_list.node(*child)).all(|child| {
+                    (child.node_type == "text" && child.text.trim().is_empty()) || child.node_type == "hardBreak"
+                })
+        })
+    };
+    let children = match node_list.node(1) {
+        Some(doc) => doc.children.clone(),
+        None => return,
+    };
+    let start = children.iter().position(|child| !is_blank(node_list, *child)).unwrap_or(children.len());
+    let end = children.iter().rposition(|child| !is_blank(node_list, *child)).map_or(start, |last| last + 1);
+    if let Some(doc) = node_list.node_mut(1) {
+        doc.children = children[start..end].to_vec();
+    }
+}
+
+/**
+ * Remove the cells without content from the end of every row.
+ */
+fn omit_trailing_empty_cells(node_list: &mut NodeList) {
+    for handle in 1..=node_list.nodes.len() {
+        if node_list.nodes[handle - 1].node_type != "tableRow" {
+            continue;
+        }
+        while let Some(&last) = node_list.nodes[handle - 1].children.last() {
+            if node_list.has_content(last) {
+                break;
+            }
+            node_list.nodes[handle - 1].children.pop();
+        }
+    }
+}
+
+/**
+ * An <ol reversed> counts down. ADF lists only count up, so the items are put in reverse order,
+ * numbered upwards from the number of the last item.
+ */
+fn reverse_lists(tree: &Tree<Node>, node_list: &mut NodeList, options: &ConverterOptions) {
+    tree.nodes()
+        .filter_map(ElementRef::wrap)
+        .filter(|element| element.value().name() == "ol" && element.value().attr("reversed").is_some())
+        .for_each(|element| {
+            let handle = match node_list.handles.get(&element.id()).copied() {
+                Some(handle) => handle,
+                None => return,
+            };
+            if let Some(list) = node_list.node_mut(handle) {
+                list.children.reverse();
+                let count = list.children.len() as u64;
+                let start = list_start(&element, options).unwrap_or(count);
+                let order = start.saturating_sub(count.saturating_sub(1));
+                list.attributes.retain(|(key, _)| key != "order");
+                list.attributes.push(("order".to_string(), Value::Number(serde_json::Number::from(order))));
+            }
+        });
+}
+
+/**
+ * The number of the first item of an <ol>: its `start`, or failing that the `value` of its first item.
+ */
+fn list_start(element: &ElementRef, options: &ConverterOptions) -> Option<u64> {
+    let number = |value: &str| value.trim().parse::<u64>().ok();
+    match element.value().attr("start") {
+        Some(start) => number(start),
+        None if options.list_item_value_policy == ListItemValuePolicy::StartList => element
+            .children()
+            .filter_map(ElementRef::wrap)
+            .find(|child| child.value().name() == "li")
+            .and_then(|item| item.value().attr("value"))
+            .and_then(number),
+        None => None,
+    }
+}
+
+/**
+ * The summary of a table becomes a paragraph right before it.
+ */
+fn add_table_summaries(tree: &Tree<Node>, node_list: &mut NodeList) {
+    tree.nodes()
+        .filter_map(ElementRef::wrap)
+        .filter(|element| element.value().name() == "table")
+        .for_each(|element| {
+            let summary = match element.value().attr("summary").map(str::trim) {
+                Some(summary) if !summary.is_empty() => summary,
+                _ => return,
+            };
+            let (table, parent) = match node_list.handles.get(&element.id()).copied() {
+                Some(table) => (table, node_list.nodes[table - 1].parent),
+                None => return,
+            };
+            let paragraph = node_list.push_anon(parent, "paragraph".to_string(), "".to_string(), &[], vec![]);
+            node_list.push_anon(paragraph, "text".to_string(), summary.to_string(), &[], vec![]);
+            node_list.move_before(paragraph, table);
+        });
+}
+
+/**
+ * HTML permits <tfoot> to appear before <tbody> in the source.
+ * Footer rows are always moved after the body rows of their table.
+ */
+fn move_footer_rows_last(tree: &Tree<Node>, node_list: &mut NodeList) {
+    tree.nodes()
+        .filter(|node| ElementRef::wrap(*node).is_some_and(|e| e.value().name() == "tfoot"))
+        .flat_map(|tfoot| tfoot.children())
+        .for_each(|row| {
+            if let Some(handle) = node_list.handles.get(&row.id()).copied() {
+                node_list.move_to_end(handle);
             }
-            "iframe" => {
-                let paragraph_handle = node_list.push_anon(
-                    insertion_point,
-                    "paragraph".to_string(),
-                    "".to_string(),
-                    &[],
-                    vec![],
-                );
-                let src = attributes
-                    .clone()
-                    .into_iter()
-                    .find(|attr| attr.0.eq("src"))
-                    .unwrap_or(("".to_string(), Value::String("".to_string())))
-                    .1;
-                let mut marks: Vec<Value> = vec![];
-                insert_adf_mark(
-                    &mut marks,
-                    String::from("link"),
-                    vec![("href".to_string(), src.to_string())],
-                );
-                node_list.push_anon(
-                    paragraph_handle,
-                    "text".to_string(),
-                    "".to_string(),
-                    &[(
-                        "text".to_string(),
-                        Value::String("External Content".to_string()),
-                    )],
-                    marks,
-                );
+        });
+}
+
+/**
+ * Generate the attributes for a node of the given content type,
+ * adjusted according to our converter options.
+ */
+pub fn node_attributes(
+    content_type: &AdfContentType,
+    node: &ElementRef,
+    options: &ConverterOptions,
+) -> Vec<(String, Value)> {
+    let mut attributes = match content_type.attributes {
+        Some(attribute_generator) => attribute_generator(node),
+        _ => vec![],
+    };
+    if content_type.typename == "heading" {
+        let max_level = options.max_heading_level.clamp(1, 6) as u64;
+        attributes.iter_mut().for_each(|(key, value)| {
+            if key == "level" {
+                let level = value.as_u64().unwrap_or(1).clamp(1, max_level);
+                *value = Value::Number(serde_json::Number::from(level));
             }
-            "hr" => {
-                node_list.push_anon(
-                    insertion_point,
-                    content_type.typename.to_string(),
-                    "".to_string(),
-                    &[],
-                    vec![],
-                );
+        });
+        if options.capture_heading_ids {
+            if let Some(id) = node.value().id().map(str::trim).filter(|id| !id.is_empty()) {
+                attributes.push(("localId".to_string(), Value::String(id.to_string())));
             }
-            "p" => {
-                let paragraph_handle = node_list.push_anon(
-                    insertion_point,
-                    content_type.typename.to_string(),
-                    "".to_string(),
-                    &[],
-                    vec![],
-                );
-                node_list.push_anon(
-                    paragraph_handle,
-                    "text".to_string(),
-                    leaf.text.to_string(),
-                    &attributes,
-                    marks,
-                );
+        }
+    }
+    if content_type.typename == "orderedList" && node.value().attr("start").is_none() {
+        if let Some(start) = list_start(node, options) {
+            attributes.push(("order".to_string(), Value::Number(serde_json::Number::from(start))));
+        }
+    }
+    if content_type.typename == "panel" && !attributes.iter().any(|(key, _)| key == "panelType") {
+        if let Some(panel_type) = panel_type(node, options) {
+            attributes.push(("panelType".to_string(), Value::String(panel_type.to_string())));
+        }
+    }
+    if content_type.typename == "embedCard" {
+        rewrite_url_attribute(&mut attributes, options);
+    }
+    if content_type.typename == "expand" && options.capture_expanded_state && node.value().attr("open").is_some() {
+        attributes.push(("__expanded".to_string(), Value::Bool(true)));
+    }
+    let is_cell = content_type.typename == "tableCell" || content_type.typename == "tableHeader";
+    if is_cell && options.cell_accessibility_policy == CellAccessibilityPolicy::Preserve {
+        CELL_ACCESSIBILITY_ATTRIBUTES.iter().for_each(|name| {
+            if let Some(value) = node.value().attr(name) {
+                attributes.push((format!("html:{name}"), Value::String(value.to_string())));
             }
-            "br" => {
-                // Special handling for br tags in table cells
-                let parent_elem = leaf.node.parent()
-                    .and_then(|p| ElementRef::wrap(p))
-                    .map(|e| e.value().name().to_string());
-
-                let is_in_table_cell = parent_elem.as_deref() == Some("td") ||
-                                       parent_elem.as_deref() == Some("th") ||
-                                       (parent_elem.as_deref() == Some("p") &&
-                                        leaf.node.parent()
-                                            .and_then(|p| p.parent())
-                                            .and_then(|gp| ElementRef::wrap(gp))
-                                            .map_or(false, |e| e.value().name() == "td" || e.value().name() == "th"));
-
-                if is_in_table_cell {
-                    // Ensure we create an empty paragraph in table cells with just <br>
-                    let cell_or_para_handle = find_valid_insertion_point(leaf, parent, &mut node_list);
-                    node_list.push_anon(
-                        cell_or_para_handle,
-                        "paragraph".to_string(),
-                        "".to_string(),
-                        &[],
-                        vec![],
-                    );
-                } else {
-                    // Exact same as default handling
-                    // If a sibling node has already wrapped itself in a paragraph, we will try to use the same paragraph
-                    if (insertion_node.is_none()
-                        || !is_valid_child_type(
-                            &insertion_node.unwrap().node_type,
-                            "text",
-                            0,
-                        )
-                        || insertion_point == 1)
-                        && (
-                            content_type.typename.eq("text") ||
-                            content_type.typename.eq("hardBreak")
-                        )
-                    {
-                        let parent_node = node_list.node(parent);
-                        if parent_node.is_some()
-                            && is_valid_child_type(&parent_node.unwrap().node_type, "paragraph", 0)
-                        {
-                            insertion_point = parent
-                        }
-                        let insertion_node = node_list.node(insertion_point);
-                        current_paragraph_handle = if current_paragraph_handle != 0
-                            && insertion_node.is_some()
-                            && *insertion_node.unwrap().children.last().unwrap_or(&0)
-                                == current_paragraph_handle
-                        {
-                            current_paragraph_handle
-                        } else {
-                            node_list.push_anon(
-                                insertion_point,
-                                "paragraph".to_string(),
-                                "".to_string(),
-                                &[],
-                                vec![],
-                            )
-                        };
-                        if content_type.typename.eq("text") {
-                            node_list.push_anon(
-                                current_paragraph_handle,
-                                content_type.typename.to_string(),
-                                leaf.text.to_string(),
-                                &attributes,
-                                marks,
-                            );
-                        }
-                        else{
-                            node_list.push_anon(
-                                current_paragraph_handle,
-                                content_type.typename.to_string(),
-                                leaf.text.to_string(),
-                                &attributes,
-                                vec![],
-                            );
-                        }
-                    } else if insertion_node.is_some()
-                        && is_valid_child_type(
-                            &insertion_node.unwrap().node_type,
-                            &content_type.typename,
-                            insertion_node.unwrap().children.len(),
-                        )
-                    {
-                        if !leaf.text.to_string().is_empty() && !content_type.typename.eq("text") {
-                            let parent_handle = node_list.push_anon(
-                                insertion_point,
-                                content_type.typename.to_string(),
-                                "".to_string(),
-                                &attributes,
-                                vec![],
-                            );
-                            if is_valid_child_type(
-                                &content_type.typename.to_string(),
-                                "text",
-                                0,
-                            ) {
-                                node_list.push_anon(
-                                    parent_handle,
-                                    "text".to_string(),
-                                    leaf.text.to_string(),
-                                    &[],
-                                    marks,
-                                );
-                            } else {
-                                let wrapper_para_handle = node_list.push_anon(
-                                    parent_handle,
-                                    "paragraph".to_string(),
-                                    "".to_string(),
-                                    &[],
-                                    vec![],
-                                );
-                                node_list.push_anon(
-                                    wrapper_para_handle,
-                                    "text".to_string(),
-                                    leaf.text.to_string(),
-                                    &[],
-                                    marks,
-                                );
-                            }
-                        } else {
-                            node_list.push_anon(
-                                insertion_point,
-                                content_type.typename.to_string(),
-                                leaf.text.to_string(),
-                                &attributes,
-                                marks,
-                            );
-                        }
-                    }
-                }
+        });
+    }
+    apply_attribute_hooks(node, &mut attributes, options);
+    attributes
+}
+
+/**
+ * Rewrite the url attribute of a media or card node, see `rewrite_url`.
+ */
+fn rewrite_url_attribute(attributes: &mut [(String, Value)], options: &ConverterOptions) {
+    attributes
+        .iter_mut()
+        .filter(|(key, _)| key == "url")
+        .for_each(|(_, value)| {
+            if let Value::String(url) = value {
+                *url = rewrite_url(url, options);
             }
-            _ => {
-                // Text nodes must sometimes be wrapped in a paragraph to be valid.
-                // If we are a text node and our immediate parent only supports a paragraph we wrap this node inside a paragraph
-                // If a sibling node has already wrapped itself in a paragraph, we will try to use the same paragraph
-                if (insertion_node.is_none()
-                    || !is_valid_child_type(
-                        &insertion_node.unwrap().node_type,
-                        "text",
-                        0,
-                    )
-                    || insertion_point == 1)
-                    && (
-                        content_type.typename.eq("text") ||
-                        content_type.typename.eq("hardBreak")
-                    )
-                {
-                    let parent_node = node_list.node(parent);
-                    if parent_node.is_some()
-                        && is_valid_child_type(&parent_node.unwrap().node_type, "paragraph", 0)
-                    {
-                        insertion_point = parent
-                    }
-                    let insertion_node = node_list.node(insertion_point);
-                    current_paragraph_handle = if current_paragraph_handle != 0
-                        && insertion_node.is_some()
-                        && *insertion_node.unwrap().children.last().unwrap_or(&0)
-                            == current_paragraph_handle
-                    {
-                        current_paragraph_handle
-                    } else {
-                        node_list.push_anon(
-                            insertion_point,
-                            "paragraph".to_string(),
-                            "".to_string(),
-                            &[],
-                            vec![],
-                        )
-                    };
-                    if content_type.typename.eq("text") {
-                        node_list.push_anon(
-                            current_paragraph_handle,
-                            content_type.typename.to_string(),
-                            leaf.text.to_string(),
-                            &attributes,
-                            marks,
-                        );
-                    }
-                    else{
-                        node_list.push_anon(
-                            current_paragraph_handle,
-                            content_type.typename.to_string(),
-                            leaf.text.to_string(),
-                            &attributes,
-                            vec![],
-                        );
-                    }
-                } else if insertion_node.is_some()
-                    && is_valid_child_type(
-                        &insertion_node.unwrap().node_type,
-                        &content_type.typename,
-                        insertion_node.unwrap().children.len(),
-                    )
-                {
-                    if !leaf.text.to_string().is_empty() && !content_type.typename.eq("text") {
-                        let parent_handle = node_list.push_anon(
-                            insertion_point,
-                            content_type.typename.to_string(),
-                            "".to_string(),
-                            &attributes,
-                            vec![],
-                        );
-                        if is_valid_child_type(
-                            &content_type.typename.to_string(),
-                            "text",
-                            0,
-                        ) {
-                            node_list.push_anon(
-                                parent_handle,
-                                "text".to_string(),
-                                leaf.text.to_string(),
-                                &[],
-                                marks,
-                            );
-                        } else {
-                            let wrapper_para_handle = node_list.push_anon(
-                                parent_handle,
-                                "paragraph".to_string(),
-                                "".to_string(),
-                                &[],
-                                vec![],
-                            );
-                            node_list.push_anon(
-                                wrapper_para_handle,
-                                "text".to_string(),
-                                leaf.text.to_string(),
-                                &[],
-                                marks,
-                            );
-                        }
-                    } else {
-                        node_list.push_anon(
-                            insertion_point,
-                            content_type.typename.to_string(),
-                            leaf.text.to_string(),
-                            &attributes,
-                            marks,
-                        );
-                    }
-                }
+        });
+}
+
+/**
+ * Run any user supplied attribute hooks for this element,
+ * replacing existing attributes of the same name.
+ */
+fn apply_attribute_hooks(
+    node: &ElementRef,
+    attributes: &mut Vec<(String, Value)>,
+    options: &ConverterOptions,
+) {
+    options
+        .attribute_hooks
+        .iter()
+        .filter(|hook| hook.tag.eq_ignore_ascii_case(node.value().name()))
+        .flat_map(|hook| (hook.hook)(node))
+        .for_each(|(key, value)| {
+            match attributes.iter_mut().find(|attribute| attribute.0 == key) {
+                Some(attribute) => attribute.1 = value,
+                None => attributes.push((key, value)),
             }
-        }
-    });
-    node_list
+        });
 }
 
 /**
  *  Inserts the new mark using valid ADF structure into our list.
  */
 fn insert_adf_mark(marks: &mut Vec<Value>, typename: String, pairs: Vec<(String, String)>) {
+    insert_adf_mark_values(marks, typename, string_values(pairs));
+}
+
+/**
+ * Mark attributes given as strings, as JSON values.
+ */
+fn string_values(pairs: Vec<(String, String)>) -> Vec<(String, Value)> {
+    pairs.into_iter().map(|(name, value)| (name, Value::String(value))).collect()
+}
+
+/**
+ * As insert_adf_mark, for attributes of any JSON type.
+ */
+fn insert_adf_mark_values(marks: &mut Vec<Value>, typename: String, pairs: Vec<(String, Value)>) {
     let mut mark_json = Map::new();
     mark_json.insert("type".to_string(), serde_json::Value::String(typename));
 
     if !pairs.is_empty() {
-        mark_json.insert("attrs".to_string(), serde_json::Value::Object(Map::new()));
-
-        pairs.iter().for_each(|pair| {
-            mark_json[&"attrs".to_string()][pair.0.clone()] =
-                serde_json::Value::String(pair.1.clone());
-        });
+        mark_json.insert("attrs".to_string(), serde_json::Value::Object(pairs.into_iter().collect()));
     }
 
     marks.push(serde_json::Value::Object(mark_json))
@@ -462,24 +1437,23 @@ fn insert_adf_mark(marks: &mut Vec<Value>, typename: String, pairs: Vec<(String,
  * Builds a mark (Value) from an AdfMark, and adds it to a list of marks.
  */
 fn insert_mark_value(marks: &mut Vec<Value>, mark: &AdfMark, node: &ElementRef) {
-    let pair_list: Vec<(String, String)> = match &mark.attributes {
-        AdfMarkAttributes::List(pairs) => pairs.clone(),
-        AdfMarkAttributes::Generator(lambda) => {
-            lambda(node)
-        }
+    let pairs: Vec<(String, Value)> = match &mark.attributes {
+        AdfMarkAttributes::List(pairs) => string_values(pairs.clone()),
+        AdfMarkAttributes::Generator(lambda) => string_values(lambda(node)),
+        AdfMarkAttributes::Map(pairs) => pairs.clone(),
     };
-    insert_adf_mark(marks, mark.typename.clone(), pair_list);
+    insert_adf_mark_values(marks, mark.typename.clone(), pairs);
 }
 
 /**
  * Generates a list of styles from a single node.
  */
-fn extract_styles(node: &ElementRef) -> Option<Vec<Vec<String>>> {
+pub fn extract_styles(node: &ElementRef) -> Option<Vec<Vec<String>>> {
     node.value().attr("style").map(|inline_style| {
         let styles: Vec<_> = inline_style
             .split(';')
             .map(|p| {
-                return p.split(':').map(|pair| pair.trim().to_string()).collect();
+                p.split(':').map(|pair| pair.trim().to_string()).collect()
             })
             .filter(|p: &Vec<String>| p.len() == 2)
             .collect();
@@ -487,46 +1461,6 @@ fn extract_styles(node: &ElementRef) -> Option<Vec<Vec<String>>> {
     })
 }
 
-/**
- * Convert a hex or RGB(a) CSS string into a 6 character hex string to be used as a color
- * code in our ADF doc.
- */
-fn hex_code_for_color_str(color_str: String) -> Option<String> {
-    lazy_static! {
-        static ref FULLHEX: Regex = Regex::new(r"(?i)#([0-9A-F]{6})").unwrap();
-        static ref HALFHEX: Regex = Regex::new(r"(?i)#([0-9A-F])([0-9A-F])([0-9A-F])").unwrap();
-        static ref RGB: Regex =
-            Regex::new(r"(?i)RGB\(\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*\)").unwrap();
-        static ref RGBA: Regex =
-            Regex::new(r"(?i)RGBA\(\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*\)").unwrap();
-    }
-    if FULLHEX.is_match(&color_str) {
-        let captures = FULLHEX.captures(&color_str).unwrap();
-        return Some(captures[1].to_string());
-    } else if HALFHEX.is_match(&color_str) {
-        let captures = HALFHEX.captures(&color_str).unwrap();
-        let (r, g, b) = (&captures[1], &captures[2], &captures[3]);
-        return Some(format!("{r}{r}{g}{g}{b}{b}"));
-    } else if RGB.is_match(&color_str) {
-        let captures = RGB.captures(&color_str).unwrap();
-        let (r, g, b) = (&captures[1], &captures[2], &captures[3]);
-        return Some(
-            [r, g, b]
-                .map(|x| format!("{:0>2x}", x.parse::<i32>().unwrap()))
-                .join(""),
-        );
-    } else if RGBA.is_match(&color_str) {
-        let captures = RGBA.captures(&color_str).unwrap();
-        let (r, g, b) = (&captures[1], &captures[2], &captures[3]);
-        return Some(
-            [r, g, b]
-                .map(|x| format!("{:0>2x}", x.parse::<i32>().unwrap()))
-                .join(""),
-        );
-    }
-    None
-}
-
 /**
  * Build a path to our leaf node inside our node_list.
  * We accumulate marks as we descend into our tree so that our leaf nodes
@@ -538,96 +1472,312 @@ fn hex_code_for_color_str(color_str: String) -> Option<String> {
  * Returns a NodeHandle (pointer to the immediate parent), and a vector of marks that
  * should apply at this location.
  */
-fn build_parent_path(leaf: &DocNode, node_list: &mut NodeList) -> (NodeHandle, Vec<Value>) {
+fn build_parent_path(
+    leaf: &DocNode,
+    node_list: &mut NodeList,
+    options: &ConverterOptions,
+) -> (NodeHandle, Vec<Value>) {
     let mut node = leaf.node;
     let mut parent_path: Vec<ElementRef> = vec![];
     let mut marks: Vec<Value> = vec![];
     let mut current_node_handle = 0;
 
-    loop {
-        let parent_node = node.parent().and_then(ElementRef::wrap);
-        if parent_node.is_some() {
-            parent_path.push(parent_node.unwrap());
-            node = *parent_node.unwrap()
-        } else {
-            break;
-        }
+    while let Some(parent_node) = node.parent().and_then(ElementRef::wrap) {
+        parent_path.push(parent_node);
+        node = *parent_node
     }
     for node in parent_path.iter().rev() {
-        let content_type = content_type_for_node_type(node.value().name());
-        content_type.marks.iter().for_each(|mark| {
-            insert_mark_value(&mut marks, mark, node);
+        if extractor::is_unwrapped(node, options) {
+            continue;
+        }
+        let content_type = transformed_content_type(node, options);
+        push_element_marks(&mut marks, &content_type, node, options);
+        current_node_handle = push_path_node(node, &content_type, current_node_handle, node_list, || {
+            node_attributes(&content_type, node, options)
         });
-        if let Some(styles) = extract_styles(node) {
-            if let Some(color_style) = styles
-                .iter()
-                .find(|styles| styles.first().unwrap().eq_ignore_ascii_case("color"))
-            {
-                let color = color_style.get(1).unwrap().to_string();
-                if let Some(text_color) = hex_code_for_color_str(color) {
-                    insert_adf_mark(
-                        &mut marks,
-                        String::from("textColor"),
-                        vec![(String::from("color"), format!("#{text_color}"))],
-                    );
-                }
-            }
-            if let Some(decoration_style) = styles.iter().find(|styles| {
-                styles
-                    .first()
-                    .unwrap()
-                    .eq_ignore_ascii_case("text-decoration")
-            }) {
-                let decoration = decoration_style.get(1).unwrap().to_string();
-                if decoration.eq_ignore_ascii_case("underline") {
-                    insert_adf_mark(&mut marks, String::from("underline"), vec![]);
-                } else if decoration.eq_ignore_ascii_case("line-through") {
-                    insert_adf_mark(&mut marks, String::from("strike"), vec![]);
-                }
-            }
+    }
+    remove_illegal_marks(&mut marks);
+    (current_node_handle, marks)
+}
+
+/// The marks the text of an inline element receives from the element itself, through its tag
+/// (e.g. `strong` for `<b>`), its attributes and its inline styles, under the default options.
+/// Marks inherited from the element's ancestors are not included.
+///
+/// Block elements and elements without a mapping give no marks of their own.
+/// Mark attributes are read from the element, so they are always a
+/// [`AdfMarkAttributes::Map`](crate::AdfMarkAttributes::Map) of the values the document would carry.
+///
+/// ```rust
+/// use htmltoadf::{marks_for_element, parse_html};
+/// use htmltoadf::scraper::Selector;
+///
+/// let fragment = parse_html(r##"<a href="https://example.com" style="color: #ff0000">Link</a>"##.to_string());
+/// let link = fragment.select(&Selector::parse("a").unwrap()).next().unwrap();
+/// let marks = marks_for_element(link);
+/// assert_eq!(marks[0].typename, "link");
+/// assert_eq!(marks[1].typename, "textColor");
+/// ```
+pub fn marks_for_element(element: ElementRef) -> Vec<AdfMark> {
+    let content_type = content_type_for_element(&element);
+    if content_type.typename != "text" || !NODE_MAP.contains_key(element.value().name()) {
+        return vec![];
+    }
+    let mut marks = vec![];
+    push_element_marks(&mut marks, content_type, &element, &ConverterOptions::default());
+    marks
+        .iter()
+        .map(|mark| AdfMark {
+            typename: mark["type"].as_str().unwrap_or_default().to_string(),
+            attributes: AdfMarkAttributes::Map(
+                mark["attrs"]
+                    .as_object()
+                    .map(|attrs| attrs.iter().map(|(name, value)| (name.clone(), value.clone())).collect())
+                    .unwrap_or_default(),
+            ),
+        })
+        .collect()
+}
+
+/// Convert a single leaf (as returned by [`extract_leaves`](crate::extract_leaves)) into the ADF
+/// node it becomes, under the default options and independent of the rest of the document.
+///
+/// The leaf carries the marks its ancestors give it (e.g. `strong` within a `<b>`), and the attributes
+/// of the element it is converted from. Inline leaves become the inline node itself, without the
+/// paragraph that would hold it in a document, unless their text converts into more than one node.
+/// A leaf that converts into nothing (such as whitespace only text) gives `Value::Null`.
+///
+/// ```rust
+/// use htmltoadf::{doc_node_to_value, extract_leaves, parse_html, ConverterOptions};
+/// use serde_json::json;
+///
+/// let html = parse_html("<p>Plain <b>bold</b></p>".to_string());
+/// let leaves = extract_leaves(&html, &ConverterOptions::default());
+/// assert_eq!(
+///     doc_node_to_value(&leaves[1]),
+///     json!({"type": "text", "text": "bold", "marks": [{"type": "strong"}]})
+/// );
+/// ```
+pub fn doc_node_to_value(leaf: &DocNode) -> Value {
+    let options = ConverterOptions::default();
+    let mut node_list = NodeList::default();
+    if is_assembled(leaf) {
+        let ancestors: Vec<ElementRef> = leaf.node.ancestors().filter_map(ElementRef::wrap).collect();
+        let mut marks = vec![];
+        for element in ancestors.iter().rev().filter(|element| !extractor::is_unwrapped(element, &options)) {
+            push_element_marks(&mut marks, content_type_for_element(element), element, &options);
         }
+        remove_illegal_marks(&mut marks);
+        let doc = node_list.push_anon(0, "doc".to_string(), "".to_string(), &[], vec![]);
+        assemble_leaf(leaf, doc, marks, &mut node_list, &mut 0, &options);
+        strip_empty_marks(&mut node_list);
+    }
+    let mut value = node_list.to_value()["content"].get(0).cloned().unwrap_or_default();
+    let is_wrapped = leaf.name != "p" && value["type"] == "paragraph";
+    match value.get_mut("content").and_then(Value::as_array_mut) {
+        Some(content) if is_wrapped && content.len() == 1 => content.remove(0),
+        _ => value,
+    }
+}
 
-        if let Some(current_node) = node_list.node(current_node_handle) {
-            if content_type.typename == "text"
-                || !is_valid_child_type(
-                    &current_node.node_type,
-                    &content_type.typename,
-                    current_node.children.len(),
-                )
-            {
-                continue;
+/**
+ * Add the marks introduced by an element (through its tag or its inline styles)
+ * to the marks accumulated by its ancestors.
+ */
+pub fn push_element_marks(
+    marks: &mut Vec<Value>,
+    content_type: &AdfContentType,
+    node: &ElementRef,
+    options: &ConverterOptions,
+) {
+    let inherited = marks.len();
+    content_type.marks.iter().for_each(|mark| {
+        insert_mark_value(marks, mark, node);
+    });
+    marks[inherited..].iter_mut().for_each(|mark| {
+        if let Some(Value::String(href)) = mark.pointer_mut("/attrs/href") {
+            *href = rewrite_url(href, options);
+        }
+    });
+    if options.muted_small_text && node.value().name() == "small" {
+        insert_adf_mark(
+            marks,
+            String::from("textColor"),
+            vec![(String::from("color"), String::from(MUTED_TEXT_COLOR))],
+        );
+    }
+    if options.italic_abbreviations && node.value().name() == "abbr" {
+        insert_adf_mark(marks, String::from("em"), vec![]);
+    }
+    if let Some(cite) = quotation_source(node) {
+        insert_adf_mark(marks, String::from("link"), vec![(String::from("href"), rewrite_url(cite, options))]);
+    }
+    if let Some(color) = highlight_color(node) {
+        insert_adf_mark(
+            marks,
+            String::from("backgroundColor"),
+            vec![(String::from("color"), format!("#{color}"))],
+        );
+    }
+    if let Some(annotation_id) = annotation_id(node) {
+        insert_adf_mark(
+            marks,
+            String::from("annotation"),
+            vec![
+                (String::from("id"), annotation_id.to_string()),
+                (String::from("annotationType"), String::from("inlineComment")),
+            ],
+        );
+    }
+    if let Some(text_color) = text_color(node) {
+        insert_adf_mark(
+            marks,
+            String::from("textColor"),
+            vec![(String::from("color"), format!("#{text_color}"))],
+        );
+    }
+    if let Some(styles) = extract_styles(node) {
+        if let Some(decoration_style) = styles.iter().find(|styles| {
+            styles
+                .first()
+                .unwrap()
+                .eq_ignore_ascii_case("text-decoration")
+        }) {
+            let decoration = decoration_style.get(1).unwrap().to_string();
+            if decoration.eq_ignore_ascii_case("underline") {
+                insert_adf_mark(marks, String::from("underline"), vec![]);
+            } else if decoration.eq_ignore_ascii_case("line-through") {
+                insert_adf_mark(marks, String::from("strike"), vec![]);
             }
         }
+        if options.monospace_code && styles.iter().any(|style| is_monospace_font(style)) {
+            insert_adf_mark(marks, String::from("code"), vec![]);
+        }
+    }
+}
 
-        let attributes = if let Some(attribute_generator) = content_type.attributes {
-            attribute_generator(node)
-        } else {
-            vec![]
-        };
+/**
+ * Returns true for a `font-family` style naming a monospace font, or the generic monospace family.
+ */
+fn is_monospace_font(style: &[String]) -> bool {
+    style[0].eq_ignore_ascii_case("font-family")
+        && style[1].split(',').any(|family| {
+            let family = family.trim().trim_matches(|c| c == '"' || c == '\'').to_ascii_lowercase();
+            MONOSPACE_FONTS.iter().any(|font| family.contains(font))
+        })
+}
+
+/**
+ * The source of an inline quotation, `<q cite="...">`, which its text links to.
+ */
+fn quotation_source<'a>(node: &ElementRef<'a>) -> Option<&'a str> {
+    if node.value().name() != "q" {
+        return None;
+    }
+    node.value().attr("cite").map(str::trim).filter(|cite| !cite.is_empty())
+}
+
+/**
+ * The text color of an element, as a 6 character hex string: its color style, or failing that the
+ * color attribute of a `<font>`. Colors we cannot parse are dropped.
+ */
+fn text_color(node: &ElementRef) -> Option<String> {
+    let style_color = extract_styles(node).and_then(|styles| {
+        styles
+            .into_iter()
+            .find(|style| style[0].eq_ignore_ascii_case("color"))
+            .map(|style| style[1].clone())
+    });
+    match style_color {
+        Some(color) => hex_code_for_color_str(&color),
+        None if node.value().name() == "font" => node.value().attr("color").and_then(hex_code_for_color_str),
+        None => None,
+    }
+}
+
+/**
+ * The highlight color of a `<mark data-color="...">`, as a 6 character hex string.
+ * A `<mark>` without a color is highlighted in the yellow of the editor, one with a color we cannot parse is not highlighted.
+ */
+fn highlight_color(node: &ElementRef) -> Option<String> {
+    if node.value().name() != "mark" {
+        return None;
+    }
+    match node.value().attr("data-color") {
+        Some(color) => hex_code_for_color_str(color),
+        None => Some(DEFAULT_HIGHLIGHT_COLOR.to_string()),
+    }
+}
+
+/**
+ * The id of the inline comment a span marks, as Confluence writes them: `<span data-annotation-id="...">`.
+ * Some editors export inline comments as highlighted text instead, `<mark data-annotation-id="...">`.
+ */
+fn annotation_id<'a>(node: &ElementRef<'a>) -> Option<&'a str> {
+    if node.value().name() != "span" && node.value().name() != "mark" {
+        return None;
+    }
+    node.value()
+        .attr("data-annotation-id")
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+}
+
+/**
+ * Extend a parent path by the node for the given element, beneath the current node.
+ * Elements already in our node list are reused, and elements that have no legal place
+ * beneath the current node are skipped.
+ *
+ * Returns the handle of the node the path continues from.
+ */
+pub fn push_path_node(
+    node: &ElementRef,
+    content_type: &AdfContentType,
+    current_node_handle: NodeHandle,
+    node_list: &mut NodeList,
+    attributes: impl FnOnce() -> Vec<(String, Value)>,
+) -> NodeHandle {
+    // A <pre> is either a code block leaf, or preformatted text that we keep inline
+    if node.value().name() == "pre" {
+        return current_node_handle;
+    }
 
-        match node_list.push(
-            node.id(),
-            current_node_handle,
-            content_type.typename.to_string(),
-            "".to_string(),
-            attributes,
-            vec![],
-        ) {
-            Ok(next_node_handle) => current_node_handle = next_node_handle,
-            Err(next_node_handle) => current_node_handle = next_node_handle,
+    if let Some(current_node) = node_list.node(current_node_handle) {
+        if content_type.typename == "text"
+            || !is_valid_child_type(
+                &current_node.node_type,
+                &content_type.typename,
+                current_node.children.len(),
+            )
+        {
+            return current_node_handle;
         }
     }
-    remove_illegal_marks(&mut marks);
-    (current_node_handle, marks)
+
+    if let Some(handle) = node_list.handles.get(&node.id()) {
+        return *handle;
+    }
+    match node_list.push(
+        node.id(),
+        current_node_handle,
+        content_type.typename.to_string(),
+        "".to_string(),
+        attributes(),
+        vec![],
+    ) {
+        Ok(next_node_handle) => next_node_handle,
+        Err(next_node_handle) => next_node_handle,
+    }
 }
 
 /**
  * Remove marks from our document not permitted by the ADF JSON Schema.
  */
-fn remove_illegal_marks(marks: &mut Vec<Value>) {
+pub fn remove_illegal_marks(marks: &mut Vec<Value>) {
     let code_value = serde_json::Value::String("code".to_string());
     let link_value = serde_json::Value::String("link".to_string());
-    let legal_code_types = [&code_value, &link_value];
+    let annotation_value = serde_json::Value::String("annotation".to_string());
+    let legal_code_types = [&code_value, &link_value, &annotation_value];
     if marks.iter().any(|m| m["type"].eq(&code_value)) {
         marks.retain(|m| legal_code_types.contains(&&m["type"]));
     }
@@ -645,6 +1795,17 @@ pub fn find_valid_insertion_point(
     node_list: &mut NodeList,
 ) -> NodeHandle {
     let content_type = content_type_for_node_type(leaf.name);
+    find_valid_insertion_point_for_type(&content_type.typename, parent, node_list)
+}
+
+/**
+ * As find_valid_insertion_point, for a node of the given type.
+ */
+fn find_valid_insertion_point_for_type(
+    typename: &str,
+    parent: NodeHandle,
+    node_list: &mut NodeList,
+) -> NodeHandle {
     let mut parent_handle: NodeHandle = parent;
     while let Some(parent_node) = node_list.node(parent_handle) {
         //We are at the top level, must insert here
@@ -653,13 +1814,12 @@ pub fn find_valid_insertion_point(
         }
 
         // We have found a valid insertion point. Good to insert here.
-        if is_valid_child_type(&parent_node.node_type, &content_type.typename, 0) {
+        if is_valid_child_type(&parent_node.node_type, typename, 0) {
             break;
         }
 
         //We are of type text, but can only insert paragraph. This is ok (We will become type paragraph instead)
-        if content_type.typename.eq(&String::from("text"))
-            && is_valid_child_type(&parent_node.node_type, "paragraph", 0)
+        if typename == "text" && is_valid_child_type(&parent_node.node_type, "paragraph", 0)
         {
             break;
         }
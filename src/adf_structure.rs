@@ -1,12 +1,20 @@
+use scraper::ElementRef;
 use serde_json::Value;
 use serde_json::json;  // Ensure this import is here
 use std::collections::HashMap;
 
+use crate::adf_builder::extract_styles;
+use crate::dates::time_element_timestamp;
 use crate::types::{
     adf_content_type::{AdfContentType, AdfMark, AdfMarkAttributes},
     adf_permitted_children::AdfPermittedChildren,
 };
 
+/**
+ * The layouts permitted on a mediaSingle node.
+ */
+static MEDIA_SINGLE_LAYOUTS: [&str; 7] = ["center", "wrap-left", "wrap-right", "wide", "full-width", "align-start", "align-end"];
+
 lazy_static! {
   /**
    * Placeholder empty child type. Does not permit any child types.
@@ -19,11 +27,11 @@ lazy_static! {
   pub static ref LEGAL_CHILD_TYPES: HashMap<String, AdfPermittedChildren> = HashMap::from([
     (
       String::from("paragraph"),
-      AdfPermittedChildren::any(&["text", "emoji", "hardBreak"])
+      AdfPermittedChildren::any(&["text", "emoji", "hardBreak", "date"])
     ),
     (
       String::from("heading"),
-      AdfPermittedChildren::any(&["text", "emoji", "hardBreak"])
+      AdfPermittedChildren::any(&["text", "emoji", "hardBreak", "date"])
     ),
     (
       String::from("bulletList"),
@@ -35,11 +43,19 @@ lazy_static! {
     ),
     (
       String::from("blockquote"),
-      AdfPermittedChildren::any(&["paragraph"])
+      AdfPermittedChildren::any(&["paragraph", "bulletList", "orderedList", "heading", "codeBlock", "mediaSingle", "mediaGroup"])
     ),
     (
       String::from("codeBlock"),
-      AdfPermittedChildren::any(&["paragraph"])
+      AdfPermittedChildren::any(&["text"])
+    ),
+    (
+      String::from("panel"),
+      AdfPermittedChildren::any(&["paragraph", "bulletList", "orderedList", "heading", "codeBlock", "blockCard", "mediaGroup", "mediaSingle", "rule", "decisionList", "taskList"])
+    ),
+    (
+      String::from("expand"),
+      AdfPermittedChildren::any(&["paragraph", "bulletList", "orderedList", "heading", "codeBlock", "blockquote", "mediaSingle", "mediaGroup", "panel", "rule", "table", "embedCard"])
     ),
     (
       String::from("listItem"),
@@ -67,6 +83,26 @@ lazy_static! {
     )
   ]);
 
+  /**
+   * ARIA_HEADING_TYPE: Any element marked up with role="heading" is treated as a heading.
+   */
+  pub static ref ARIA_HEADING_TYPE: AdfContentType = AdfContentType::from_name_and_attributes("heading", heading_attributes);
+
+  /**
+   * EMOJI_TYPE: Images exported by Atlassian products for an emoji (with data-emoji-short-name) are emoji again.
+   */
+  pub static ref EMOJI_TYPE: AdfContentType = AdfContentType::from_name_and_attributes("emoji", emoji_attributes);
+
+  /**
+   * DATE_TYPE: A <time> naming a day (rather than a duration, or a time of day) is a date.
+   */
+  pub static ref DATE_TYPE: AdfContentType = AdfContentType::from_name_and_attributes("date", date_attributes);
+
+  /**
+   * PANEL_TYPE: A <div> with a class matching the panel_classes option. Its panelType is read from the options.
+   */
+  pub static ref PANEL_TYPE: AdfContentType = AdfContentType::from_name("panel");
+
   #[derive(Debug, Clone)]
   /**
    * NODE_MAP: The legal mappings from HTML element types to ADF types that we permit.
@@ -76,14 +112,48 @@ lazy_static! {
       "p",
       AdfContentType::from_name("paragraph")
     ),
+    (
+      "textarea",
+      AdfContentType::from_name("paragraph")
+    ),
+    (
+      "button",
+      AdfContentType::from_name("paragraph")
+    ),
     (
       "blockquote",
       AdfContentType::from_name("blockquote")
     ),
+    (
+      "pre",
+      AdfContentType::from_name_and_attributes("codeBlock", code_block_attributes)
+    ),
+    (
+      "details",
+      AdfContentType::from_name_and_attributes("expand", expand_attributes)
+    ),
     (
       "span",
       AdfContentType::from_name("text")
     ),
+    // ADF has no font sizes, so these only keep their text
+    (
+      "small",
+      AdfContentType::from_name("text")
+    ),
+    (
+      "big",
+      AdfContentType::from_name("text")
+    ),
+    // ADF cannot isolate or override the direction of text, so these only keep their text
+    (
+      "bdi",
+      AdfContentType::from_name("text")
+    ),
+    (
+      "bdo",
+      AdfContentType::from_name("text")
+    ),
     (
       "text",
       AdfContentType::from_name("text")
@@ -94,7 +164,13 @@ lazy_static! {
     ),
     (
       "ol",
-      AdfContentType::from_name("orderedList")
+      AdfContentType::from_name_and_attributes("orderedList", |node| {
+        // The number of the first item
+        match node.value().attr("start").and_then(|start| start.trim().parse::<u64>().ok()) {
+          Some(start) => vec![("order".to_string(), json!(start))],
+          None => vec![]
+        }
+      })
     ),
     (
       "li",
@@ -126,17 +202,20 @@ lazy_static! {
     ),
     (
       "th",
-      AdfContentType::from_name("tableHeader")
+      AdfContentType::from_name_and_attributes("tableHeader", table_cell_attributes)
     ),
     (
       "td",
-      AdfContentType::from_name("tableCell")
+      AdfContentType::from_name_and_attributes("tableCell", table_cell_attributes)
     ),
     (
       "iframe",
-      AdfContentType::from_name_and_attributes("paragraph", |node|{
+      AdfContentType::from_name_and_attributes("embedCard", |node|{
         match node.value().attr("src"){
-          Some(attribute) => vec![("src".to_string(), Value::String(attribute.to_string()))],
+          Some(attribute) => vec![
+            ("url".to_string(), Value::String(attribute.to_string())),
+            ("layout".to_string(), Value::String("center".to_string()))
+          ],
           None => vec!()
         }
       }
@@ -178,6 +257,16 @@ lazy_static! {
         }
       ])
     ),
+    // The defining instance of a term, conventionally italic
+    (
+      "dfn",
+      AdfContentType::from_name_and_marks("text", &[
+        AdfMark{
+          typename: "em".to_string(),
+          attributes: AdfMarkAttributes::List(vec!())
+        }
+      ])
+    ),
     (
       "u",
       AdfContentType::from_name_and_marks("text", &[
@@ -187,6 +276,42 @@ lazy_static! {
         }
       ])
     ),
+    (
+      "ins",
+      AdfContentType::from_name_and_marks("text", &[
+        AdfMark{
+          typename: "underline".to_string(),
+          attributes: AdfMarkAttributes::List(vec!())
+        }
+      ])
+    ),
+    (
+      "s",
+      AdfContentType::from_name_and_marks("text", &[
+        AdfMark{
+          typename: "strike".to_string(),
+          attributes: AdfMarkAttributes::List(vec!())
+        }
+      ])
+    ),
+    (
+      "del",
+      AdfContentType::from_name_and_marks("text", &[
+        AdfMark{
+          typename: "strike".to_string(),
+          attributes: AdfMarkAttributes::List(vec!())
+        }
+      ])
+    ),
+    (
+      "strike",
+      AdfContentType::from_name_and_marks("text", &[
+        AdfMark{
+          typename: "strike".to_string(),
+          attributes: AdfMarkAttributes::List(vec!())
+        }
+      ])
+    ),
     (
       "code",
       AdfContentType::from_name_and_marks("text", &[
@@ -232,133 +357,403 @@ lazy_static! {
     ),
     (
       "h1",
-      AdfContentType::from_name_and_attributes("heading", |_|
-        {
-          vec!(
-            ("level".to_string(), Value::Number(serde_json::Number::from(1))),
-          )
-        }
-      )
+      AdfContentType::from_name_and_attributes("heading", heading_attributes)
     ),
     (
       "h2",
-      AdfContentType::from_name_and_attributes("heading", |_|
-        {
-          vec!(
-            ("level".to_string(), Value::Number(serde_json::Number::from(2))),
-          )
-        }
-      )
+      AdfContentType::from_name_and_attributes("heading", heading_attributes)
     ),
     (
       "h3",
-      AdfContentType::from_name_and_attributes("heading", |_|
-        {
-          vec!(
-            ("level".to_string(), Value::Number(serde_json::Number::from(3))),
-          )
-        }
-      )
+      AdfContentType::from_name_and_attributes("heading", heading_attributes)
     ),
     (
       "h4",
-      AdfContentType::from_name_and_attributes("heading", |_|
-        {
-          vec!(
-            ("level".to_string(), Value::Number(serde_json::Number::from(4))),
-          )
-        }
-      )
+      AdfContentType::from_name_and_attributes("heading", heading_attributes)
     ),
     (
       "h5",
-      AdfContentType::from_name_and_attributes("heading", |_|
-        {
-          vec!(
-            ("level".to_string(), Value::Number(serde_json::Number::from(5))),
-          )
-        }
-      )
+      AdfContentType::from_name_and_attributes("heading", heading_attributes)
     ),
     (
       "h6",
-      AdfContentType::from_name_and_attributes("heading", |_|
-        {
-          vec!(
-            ("level".to_string(), Value::Number(serde_json::Number::from(6))),
-          )
-        }
-      )
+      AdfContentType::from_name_and_attributes("heading", heading_attributes)
     ),
     (
       "img",
-      AdfContentType::from_name_and_children("mediaSingle", |node| {
-        let attrs = node.value();
-        
-        // Check for layout attribute or default to "center"
-        let layout = attrs.attr("data-layout").unwrap_or("center");
-        
-        // Create mediaSingle attributes
-        let mut result = vec![
-          ("layout".to_string(), Value::String(layout.to_string()))
-        ];
-        
-        // Create child node for media
-        let mut child_node = json!({
-          "type": "media",
-          "attrs": {}
-        });
-        
-        // Check if this is an external or file-based media
-        if let Some(src) = attrs.attr("src") {
-          // External media
-          child_node["attrs"] = json!({
-            "url": src,
-            "type": "external"
-          });
-        } else if let Some(media_id) = attrs.attr("data-media-id") {
-          // File-based media
-          let mut media_attrs = json!({
-            "id": media_id,
-            "type": "file"
-          });
-          
-          // Add optional attributes if present
-          if let Some(collection) = attrs.attr("data-collection") {
-            media_attrs["collection"] = json!(collection);
-          }
-          
-          if let Some(alt) = attrs.attr("alt") {
-            media_attrs["alt"] = json!(alt);
-          }
-          
-          // Handle width with optional width type
-          if let Some(width) = attrs.attr("data-width") {
-            if let Ok(width_val) = width.parse::<i64>() {
-              media_attrs["width"] = json!(width_val);
-            }
-          }
-          
-          // Handle height
-          if let Some(height) = attrs.attr("data-height") {
-            if let Ok(height_val) = height.parse::<i64>() {
-              media_attrs["height"] = json!(height_val);
-            }
-          }
-          
-          // Handle width type (pixel or percentage)
-          if let Some(width_type) = attrs.attr("data-width-type") {
-            if width_type == "pixel" || width_type == "percentage" {
-              media_attrs["widthType"] = json!(width_type);
-            }
-          }
-          
-          child_node["attrs"] = media_attrs;
-        }
-        
-        // Return the attributes and children
-        (result, vec![child_node])
-      })
+      AdfContentType::from_name_and_children("mediaSingle", image_children)
+    ),
+    (
+      "figure",
+      AdfContentType::from_name_and_children("mediaSingle", figure_children)
     ),
 ]);
 }
+
+/**
+ * A code block takes its language from a `language-*` (or `lang-*`) class, as used by most highlighters,
+ * or failing that from a `data-language`, `lang` or `xml:lang` attribute.
+ * Either sits on the <pre> itself or on the <code> element it wraps. Common aliases (`js`) are expanded.
+ */
+fn code_block_attributes(node: &ElementRef) -> Vec<(String, Value)> {
+  static LANGUAGE_ATTRIBUTES: [&str; 3] = ["data-language", "lang", "xml:lang"];
+  let code = node
+    .children()
+    .filter_map(ElementRef::wrap)
+    .find(|child| child.value().name() == "code");
+  let elements = || std::iter::once(*node).chain(code);
+  let language = elements()
+    .flat_map(|element| element.value().classes())
+    .find_map(|class| class.strip_prefix("language-").or_else(|| class.strip_prefix("lang-")))
+    .filter(|language| !language.is_empty())
+    .or_else(|| {
+      LANGUAGE_ATTRIBUTES
+        .iter()
+        .flat_map(|name| elements().filter_map(move |element| element.value().attr(name)))
+        .map(str::trim)
+        .find(|language| !language.is_empty())
+    });
+  match language {
+    Some(language) => vec![("language".to_string(), Value::String(normalize_language(language)))],
+    None => vec![]
+  }
+}
+
+/**
+ * Expand the common short names of languages into the names ADF uses.
+ */
+fn normalize_language(language: &str) -> String {
+  static ALIASES: [(&str, &str); 9] = [
+    ("js", "javascript"),
+    ("ts", "typescript"),
+    ("py", "python"),
+    ("rb", "ruby"),
+    ("sh", "shell"),
+    ("yml", "yaml"),
+    ("md", "markdown"),
+    ("cs", "csharp"),
+    ("c++", "cpp"),
+  ];
+  let language = language.to_ascii_lowercase();
+  match ALIASES.iter().find(|(alias, _)| *alias == language) {
+    Some((_, name)) => name.to_string(),
+    None => language
+  }
+}
+
+/**
+ * The day a date names, as a timestamp in milliseconds (which ADF gives as a string).
+ */
+fn date_attributes(node: &ElementRef) -> Vec<(String, Value)> {
+  match time_element_timestamp(node) {
+    Some(timestamp) => vec![("timestamp".to_string(), json!(timestamp.to_string()))],
+    None => vec![]
+  }
+}
+
+/**
+ * The short name of an emoji image, along with its id and text where given.
+ */
+fn emoji_attributes(node: &ElementRef) -> Vec<(String, Value)> {
+  [("shortName", "data-emoji-short-name"), ("id", "data-emoji-id"), ("text", "data-emoji-text")]
+    .iter()
+    .filter_map(|(name, attribute)| {
+      node.value().attr(attribute).map(|value| (name.to_string(), Value::String(value.to_string())))
+    })
+    .collect()
+}
+
+/**
+ * Attributes shared by all headings. The level is taken from the tag name (h1-h6)
+ * or from aria-level for ARIA headings, defaulting to 1 when missing or invalid.
+ */
+fn heading_attributes(node: &ElementRef) -> Vec<(String, Value)> {
+  let level = node
+    .value()
+    .name()
+    .strip_prefix('h')
+    .and_then(|level| level.parse::<u64>().ok())
+    .or_else(|| node.value().attr("aria-level").and_then(|level| level.trim().parse::<u64>().ok()))
+    .filter(|level| *level >= 1)
+    .unwrap_or(1);
+  vec![("level".to_string(), Value::Number(serde_json::Number::from(level)))]
+}
+
+/**
+ * Attributes shared by tableCell and tableHeader nodes.
+ * A cell spanning several columns carries one colwidth entry per spanned column.
+ * Widths are read from data-colwidth (a comma separated list) or a pixel width style.
+ * A single width is divided over the spanned columns, a list of any other length is dropped.
+ */
+fn table_cell_attributes(node: &ElementRef) -> Vec<(String, Value)> {
+  let mut attributes = vec![];
+  let colspan = cell_span(node, "colspan");
+
+  if let Some(colspan) = colspan {
+    attributes.push(("colspan".to_string(), json!(colspan)));
+  }
+
+  if let Some(rowspan) = cell_span(node, "rowspan") {
+    attributes.push(("rowspan".to_string(), json!(rowspan)));
+  }
+
+  let span = colspan.unwrap_or(1);
+  let colwidth: Vec<u64> = if let Some(widths) = node.value().attr("data-colwidth") {
+    let widths: Vec<u64> = widths
+      .split(',')
+      .filter_map(parse_dimension)
+      .collect();
+    match widths.len() as u64 {
+      1 => divide_width(widths[0], span),
+      len if len == span => widths,
+      _ => vec![]
+    }
+  } else {
+    extract_styles(node)
+      .unwrap_or_default()
+      .iter()
+      .find(|style| style[0].eq_ignore_ascii_case("width"))
+      .and_then(|style| parse_dimension(&style[1]))
+      .map(|width| divide_width(width, span))
+      .unwrap_or_default()
+  };
+
+  if !colwidth.is_empty() {
+    attributes.push(("colwidth".to_string(), json!(colwidth)));
+  }
+  attributes
+}
+
+/**
+ * Read a colspan or rowspan attribute. Values are clamped to the same limits browsers apply,
+ * and a span of a single cell is omitted.
+ */
+fn cell_span(node: &ElementRef, name: &str) -> Option<u64> {
+  let max = if name == "rowspan" { 65534 } else { 1000 };
+  node
+    .value()
+    .attr(name)
+    .and_then(|span| span.trim().parse::<u64>().ok())
+    .map(|span| span.min(max))
+    .filter(|span| *span > 1)
+}
+
+/**
+ * An image becomes a mediaSingle holding a single media node, external or referring to a Confluence file.
+ */
+fn image_children(node: &ElementRef) -> (Vec<(String, Value)>, Vec<Value>) {
+  let attrs = node.value();
+  
+  // Only emit a layout when one is explicitly requested, falling back to "center"
+  // for layouts that ADF does not support
+  let mut result = match attrs.attr("data-layout") {
+    Some(layout) => {
+      let layout = if MEDIA_SINGLE_LAYOUTS.contains(&layout) { layout } else { "center" };
+      vec![("layout".to_string(), Value::String(layout.to_string()))]
+    },
+    None => vec![]
+  };
+
+  // The display width of the mediaSingle itself, as opposed to the media's own dimensions.
+  // Without a widthType the width is a percentage, so is clamped to 100.
+  let width_type = attrs
+    .attr("data-media-single-width-type")
+    .filter(|width_type| *width_type == "pixel" || *width_type == "percentage");
+  let width = attrs
+    .attr("data-media-single-width")
+    .and_then(|width| parse_width(width, width_type != Some("pixel")));
+  if let Some(width) = width {
+    result.push(("width".to_string(), json!(width)));
+    if let Some(width_type) = width_type {
+      result.push(("widthType".to_string(), json!(width_type)));
+    }
+  }
+
+  // Create child node for media
+  let mut child_node = json!({
+    "type": "media",
+    "attrs": {}
+  });
+  
+  // Check if this is an external or file-based media.
+  // Other image attributes (loading, decoding, sizes, fetchpriority etc) have no bearing on the node.
+  if let Some(src) = image_source(node) {
+    // External media
+    child_node["attrs"] = json!({
+      "url": src,
+      "type": "external"
+    });
+    if let Some(alt) = attrs.attr("alt") {
+      child_node["attrs"]["alt"] = json!(alt);
+    }
+  } else if let Some(media_id) = attrs.attr("data-media-id") {
+    // File-based media
+    let mut media_attrs = json!({
+      "id": media_id,
+      "type": "file"
+    });
+    
+    // Add optional attributes if present
+    if let Some(collection) = attrs.attr("data-collection") {
+      media_attrs["collection"] = json!(collection);
+    }
+    
+    if let Some(alt) = attrs.attr("alt") {
+      media_attrs["alt"] = json!(alt);
+    }
+    
+    // Handle width with optional width type
+    if let Some(width) = attrs.attr("data-width") {
+      if let Some(width_val) = parse_width(width, attrs.attr("data-width-type") == Some("percentage")) {
+        media_attrs["width"] = json!(width_val);
+      }
+    }
+    
+    // Handle height
+    if let Some(height) = attrs.attr("data-height") {
+      if let Some(height_val) = parse_dimension(height) {
+        media_attrs["height"] = json!(height_val);
+      }
+    }
+    
+    // Handle width type (pixel or percentage)
+    if let Some(width_type) = attrs.attr("data-width-type") {
+      if width_type == "pixel" || width_type == "percentage" {
+        media_attrs["widthType"] = json!(width_type);
+      }
+    }
+    
+    child_node["attrs"] = media_attrs;
+  }
+  
+  // Return the attributes and children
+  (result, vec![child_node])
+}
+
+/**
+ * A figure becomes the mediaSingle of the image it holds, with the text of its <figcaption> as the caption.
+ * When assembled, the caption is converted from the inline content of the <figcaption> instead, keeping its marks.
+ */
+fn figure_children(node: &ElementRef) -> (Vec<(String, Value)>, Vec<Value>) {
+  let descendants = || node.descendants().filter_map(ElementRef::wrap);
+  let (attributes, mut children) = descendants()
+    .find(|descendant| descendant.value().name() == "img")
+    .map(|image| image_children(&image))
+    .unwrap_or_default();
+  let caption = descendants()
+    .find(|descendant| descendant.value().name() == "figcaption")
+    .map(|caption| flatten_text(&caption))
+    .unwrap_or_default();
+  if !caption.is_empty() {
+    children.push(json!({
+      "type": "caption",
+      "content": [{"type": "text", "text": caption}]
+    }));
+  }
+  (attributes, children)
+}
+
+/**
+ * All text within an element as a single line, with its whitespace collapsed (and its marks dropped).
+ */
+fn flatten_text(node: &ElementRef) -> String {
+  node.text().collect::<Vec<_>>().join("").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/**
+ * The title of an expand is a plain string, so the inline content of the <details>' <summary>
+ * is flattened into one: its marks are dropped and its text joined, with whitespace collapsed.
+ */
+fn expand_attributes(node: &ElementRef) -> Vec<(String, Value)> {
+  let summary = node
+    .children()
+    .filter_map(ElementRef::wrap)
+    .find(|child| child.value().name() == "summary");
+  let title = summary.map(|summary| flatten_text(&summary)).unwrap_or_default();
+  if title.is_empty() {
+    vec![]
+  } else {
+    vec![("title".to_string(), Value::String(title))]
+  }
+}
+
+/**
+ * The source of an external image: its src, or failing that the highest resolution candidate of its srcset.
+ * Candidates without a descriptor count as 1x, and of equal candidates the first is taken.
+ * The <img> of a <picture> is its fallback, without a source of its own the first <source> of the picture is taken.
+ */
+fn image_source(node: &ElementRef) -> Option<String> {
+  if let Some(src) = node.value().attr("src") {
+    return Some(src.to_string());
+  }
+  if let Some(srcset) = node.value().attr("srcset") {
+    return srcset_source(srcset);
+  }
+  node
+    .parent()
+    .and_then(ElementRef::wrap)
+    .filter(|parent| parent.value().name() == "picture")?
+    .children()
+    .filter_map(ElementRef::wrap)
+    .filter(|child| child.value().name() == "source")
+    .find_map(|source| source.value().attr("srcset"))
+    .and_then(srcset_source)
+}
+
+fn srcset_source(srcset: &str) -> Option<String> {
+  srcset
+    .split(',')
+    .filter_map(|candidate| {
+      let mut parts = candidate.split_whitespace();
+      let url = parts.next()?;
+      let resolution = match parts.next() {
+        Some(descriptor) => descriptor
+          .strip_suffix(['w', 'x'])
+          .and_then(|value| value.parse::<f64>().ok())?,
+        None => 1.0
+      };
+      Some((url, resolution))
+    })
+    .fold(None, |best: Option<(&str, f64)>, candidate| match best {
+      Some(best) if best.1 >= candidate.1 => Some(best),
+      _ => Some(candidate)
+    })
+    .map(|(url, _)| url.to_string())
+}
+
+/**
+ * Parse a width or height, in pixels or percent, from an attribute or style value.
+ * Dimensions are always emitted as integers, since ADF validators reject a width such as 120.0,
+ * so a decimal value is rounded. Negative and non-numeric values give None.
+ */
+fn parse_dimension(value: &str) -> Option<u64> {
+  let value = value.trim();
+  let value = value.strip_suffix("px").unwrap_or(value).trim();
+  match value.parse::<u64>() {
+    Ok(dimension) => Some(dimension),
+    Err(_) => value
+      .parse::<f64>()
+      .ok()
+      .filter(|dimension| dimension.is_finite() && *dimension >= 0.0)
+      .map(|dimension| dimension.round() as u64)
+  }
+}
+
+/**
+ * Parse a width which is either in pixels or, when `percentage` is set, a percentage.
+ * A percentage may not exceed 100, so larger values are clamped to it.
+ */
+fn parse_width(value: &str, percentage: bool) -> Option<u64> {
+  let width = if percentage { value.trim().trim_end_matches('%') } else { value };
+  parse_dimension(width).map(|width| if percentage { width.min(100) } else { width })
+}
+
+/**
+ * Divide a width over the given number of columns.
+ * Any leftover pixels go to the leading columns, so the widths always add up to the total.
+ */
+fn divide_width(width: u64, span: u64) -> Vec<u64> {
+  (0..span)
+    .map(|column| width / span + u64::from(column < width % span))
+    .collect()
+}
@@ -0,0 +1,86 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use scraper::ElementRef;
+
+/**
+ * The timestamp of the day a `<time>` element names, through its datetime attribute or, without one, its text.
+ * A `<time>` naming a day becomes a date node, any other keeps its text.
+ */
+pub fn time_element_timestamp(element: &ElementRef) -> Option<i64> {
+    if element.value().name() != "time" {
+        return None;
+    }
+    match element.value().attr("datetime") {
+        Some(datetime) => date_timestamp(datetime),
+        None => date_timestamp(&element.text().collect::<String>()),
+    }
+}
+
+/**
+ * The timestamp (milliseconds since the epoch, at midnight UTC) of the day a `<time>` machine readable value names.
+ * Dates (`2024-05-01`) and date times (`2024-05-01T09:30`, with optional seconds, fractions and time zone) name a day.
+ * A date node holds nothing but that day, so the time of a date time is dropped and its date used as written.
+ * Every other form, such as durations (`PT1H30M`), times of day, months or weeks, names no day and returns None.
+ */
+pub fn date_timestamp(datetime: &str) -> Option<i64> {
+    lazy_static! {
+        static ref DATE: Regex = Regex::new(
+            r"^(\d{4,})-(\d{2})-(\d{2})(?:[T ]\d{2}:\d{2}(?::\d{2}(?:\.\d{1,3})?)?(?:Z|[+-]\d{2}:?\d{2})?)?$"
+        )
+        .unwrap();
+    }
+    let captures = DATE.captures(datetime.trim())?;
+    let year: i64 = captures[1].parse().ok()?;
+    let month: i64 = captures[2].parse().ok()?;
+    let day: i64 = captures[3].parse().ok()?;
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day) * 86_400_000)
+}
+
+/**
+ * The date (`YYYY-MM-DD`) of a timestamp in milliseconds since the epoch, in UTC.
+ */
+pub fn timestamp_date(timestamp: i64) -> String {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(86_400_000));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/**
+ * Days since 1970-01-01 of a date in the proleptic Gregorian calendar, counting in 400 year eras
+ * that start on the 1st of March (so the leap day ends the year).
+ */
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/**
+ * The inverse of days_from_civil.
+ */
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
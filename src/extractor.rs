@@ -1,9 +1,17 @@
 use ego_tree::iter::Edge;
+use ego_tree::NodeId;
 use ego_tree::NodeRef;
 use regex::Regex;
+use scraper::node::Element;
 use scraper::Node;
 use scraper::{ElementRef, Html};
 
+use crate::adf_builder::extract_styles;
+use crate::dates::time_element_timestamp;
+use crate::types::adf_content_type::content_type_for_node_type;
+use crate::types::converter_options::ConverterOptions;
+use crate::types::converter_options::FormFieldPolicy;
+use crate::types::converter_options::UnknownElementPolicy;
 use crate::types::doc_node::DocNode;
 
 /**
@@ -18,41 +26,632 @@ use crate::types::doc_node::DocNode;
 static HRBR_PLACEHOLDER: &str = "hrbr";
 
 pub fn esc_hr(hrstr: String) -> String {
-    let re = Regex::new(r"</?hr/?>").unwrap();
-    return re
+    // Any spelling of the tag: <hr>, <HR>, <hr/>, <hr />, <hr class="divider">
+    lazy_static! {
+        static ref HR: Regex = Regex::new(r"(?i)<\s*/?\s*hr\b[^>]*>").unwrap();
+    }
+    HR
         .replace_all(&hrstr, format!("<{HRBR_PLACEHOLDER}></{HRBR_PLACEHOLDER}>"))
-        .to_string();
+        .to_string()
 }
 
-pub fn has_text_node(node: NodeRef<Node>) -> bool {
-    node.children().any(|child_node| {
-        match child_node.value() {
+/**
+ * XHTML serializers write empty elements self-closed (<iframe/>, <div/>, <a name="x"/>), but in HTML
+ * only void elements close themselves. Any other self-closed tag would stay open and swallow what follows,
+ * so we expand these into an explicit open and close tag before parsing.
+ */
+fn expand_self_closing(html: String) -> String {
+    lazy_static! {
+        static ref SELF_CLOSING: Regex = Regex::new(r"<([a-zA-Z][a-zA-Z0-9-]*)(\s[^<>]*?)?\s*/>").unwrap();
+    }
+    static VOID_ELEMENTS: [&str; 14] = [
+        "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+    ];
+    SELF_CLOSING
+        .replace_all(&html, |captures: &regex::Captures| {
+            let name = &captures[1];
+            let attributes = captures.get(2).map_or("", |attributes| attributes.as_str());
+            // In <a href=/path/> the slash belongs to an unquoted attribute value, the tag is not self-closed
+            let unquoted_value = captures[0].ends_with(&format!("{attributes}/>"))
+                && attributes
+                    .rsplit(char::is_whitespace)
+                    .next()
+                    .is_some_and(|last| last.contains('=') && !last.ends_with(['"', '\'']));
+            if unquoted_value || VOID_ELEMENTS.contains(&name.to_ascii_lowercase().as_str()) {
+                captures[0].to_string()
+            } else {
+                format!("<{name}{attributes}></{name}>")
+            }
+        })
+        .to_string()
+}
+
+/**
+ * Parse our HTML input. Full documents (starting with a doctype or `<html>` tag) are parsed
+ * as documents, anything else as a fragment. Either way the loose content ends up beneath
+ * a single root element which we convert into our doc node.
+ */
+pub fn parse_html(html: String) -> Html {
+    lazy_static! {
+        static ref DOCUMENT_START: Regex = Regex::new(r"(?i)^\s*(<!doctype|<html)").unwrap();
+    }
+    if DOCUMENT_START.is_match(&html) {
+        parse_html_document(html)
+    } else {
+        parse_html_fragment(html)
+    }
+}
+
+/**
+ * Parse our HTML input as a full document: the content of its <head> is left out, and its <body> holds the rest.
+ */
+pub fn parse_html_document(html: String) -> Html {
+    Html::parse_document(&esc_hr(expand_self_closing(html)))
+}
+
+/**
+ * Parse our HTML input as a fragment, as if it were the content of a <body>.
+ * Any <html>, <head> or <body> tags within it are ignored, though not their content.
+ */
+pub fn parse_html_fragment(html: String) -> Html {
+    Html::parse_fragment(&esc_hr(expand_self_closing(html)))
+}
+
+pub fn has_text_node(node: NodeRef<Node>, options: &ConverterOptions) -> bool {
+    // Walked without recursion, as the HTML may be nested arbitrarily deep
+    let mut skipped_depth = 0;
+    for edge in node.traverse().skip(1) {
+        let descendant = match edge {
+            Edge::Open(descendant) => descendant,
+            Edge::Close(_) => {
+                skipped_depth = skipped_depth.max(1) - 1;
+                continue;
+            }
+        };
+        if skipped_depth > 0 {
+            skipped_depth += 1;
+            continue;
+        }
+        match descendant.value() {
             Node::Element(element) => {
-                // Recursively check children like <br> or other elements that might contain text
-                 element.name() == "br" || has_text_node(child_node)
+                // Elements like <br> count, text we do not convert does not
+                if excludes_subtree(&ElementRef::wrap(descendant).unwrap(), options) {
+                    skipped_depth = 1;
+                } else if element.name() == "br" {
+                    return true;
+                }
             }
             Node::Text(text_node) => {
                 // Check based on context (inside <pre> or not)
-                if is_inside_pre(child_node) {
+                let is_significant = if is_inside_pre(descendant) {
                     !text_node.text.is_empty() // Keep if not completely empty inside <pre>
                 } else {
                     !text_node.text.trim().is_empty() // Keep if not just whitespace outside <pre>
+                };
+                if is_significant {
+                    return true;
+                }
+            }
+            _ => {} // Ignore comments, doctypes etc.
+        }
+    }
+    false
+}
+
+// Helper function to check if a node is inside a <pre> element, or another element preserving whitespace
+pub fn is_inside_pre(node: NodeRef<Node>) -> bool {
+    node.ancestors()
+        .filter_map(|ancestor| ancestor.value().as_element())
+        .any(preserves_whitespace)
+}
+
+/**
+ * Whitespace within a <pre> is kept as it is, as it is within any element styled
+ * `white-space: pre` or `white-space: pre-wrap` (which some editors use instead of a <pre>).
+ */
+pub fn preserves_whitespace(element: &Element) -> bool {
+    element.name() == "pre"
+        || element.attr("style").is_some_and(|style| {
+            style.split(';').any(|declaration| match declaration.split_once(':') {
+                Some((property, value)) => {
+                    property.trim().eq_ignore_ascii_case("white-space")
+                        && ["pre", "pre-wrap"].contains(&value.trim().to_ascii_lowercase().as_str())
+                }
+                None => false,
+            })
+        })
+}
+
+/**
+ * A <pre> element becomes a code block unless it carries formatting marks that a code block
+ * cannot hold (e.g. sub/sup or links). Spans used for syntax highlighting are ignored.
+ */
+pub fn is_code_block(element: &ElementRef) -> bool {
+    element.value().name() == "pre"
+        && !element.descendants().filter_map(ElementRef::wrap).any(|child| {
+            content_type_for_node_type(child.value().name())
+                .marks
+                .iter()
+                .any(|mark| mark.typename != "code")
+        })
+}
+
+/**
+ * Flatten all text within a code block into a single string, preserving order and whitespace.
+ * With strip_line_numbers, the line numbers a syntax highlighter rendered are left out: the text of
+ * gutter elements, or when the block has data-line-numbers, the number starting each line.
+ */
+fn code_block_text(element: &ElementRef, options: &ConverterOptions) -> String {
+    let text: String = element
+        .descendants()
+        .filter(|node| {
+            !options.strip_line_numbers
+                || !node.ancestors().take_while(|ancestor| ancestor.id() != element.id()).any(is_line_number_gutter)
+        })
+        .filter_map(|node| match node.value() {
+            Node::Text(text_node) => Some(text_node.text.to_string()),
+            Node::Element(child) if child.name() == "br" => Some("\n".to_string()),
+            _ => None,
+        })
+        .collect();
+    if options.strip_line_numbers && element.value().attr("data-line-numbers").is_some() {
+        return strip_numbered_lines(&text).unwrap_or(text);
+    }
+    text
+}
+
+/**
+ * Returns true for the elements highlighters render line numbers into, by their class
+ * (e.g. Pygments' "linenos", or "hljs-ln-numbers" of highlightjs-line-numbers).
+ */
+fn is_line_number_gutter(node: NodeRef<Node>) -> bool {
+    static GUTTER_CLASSES: [&str; 7] =
+        ["gutter", "line-number", "line-numbers-rows", "linenos", "lineno", "hljs-ln-numbers", "hljs-ln-n"];
+    node.value()
+        .as_element()
+        .is_some_and(|element| element.classes().any(|class| GUTTER_CLASSES.contains(&class)))
+}
+
+/**
+ * Remove the number starting each line, provided every line starts with one and they count up one at a time,
+ * so code merely starting with a number is left alone. None when the lines are not numbered like that.
+ */
+fn strip_numbered_lines(text: &str) -> Option<String> {
+    lazy_static! {
+        static ref LINE_NUMBER: Regex = Regex::new(r"^[ \t]*(\d+)[:|]?(?:[ \t]|$)").unwrap();
+    }
+    let lines: Vec<&str> = text.strip_suffix('\n').unwrap_or(text).split('\n').collect();
+    let mut expected: Option<u64> = None;
+    let stripped: Vec<&str> = lines
+        .iter()
+        .map(|line| {
+            let captures = LINE_NUMBER.captures(line)?;
+            let number = captures[1].parse::<u64>().ok()?;
+            if expected.is_some_and(|expected| expected != number) {
+                return None;
+            }
+            expected = Some(number + 1);
+            Some(&line[captures[0].len()..])
+        })
+        .collect::<Option<_>>()?;
+    let trailing_newline = if text.ends_with('\n') { "\n" } else { "" };
+    Some(stripped.join("\n") + trailing_newline)
+}
+
+/**
+ * A <figure> holding an image becomes a single mediaSingle, captioned by its <figcaption>.
+ */
+fn is_figure(element: &ElementRef) -> bool {
+    element.value().name() == "figure"
+        && element
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .any(|descendant| descendant.value().name() == "img")
+}
+
+/**
+ * Returns true for elements whose whole subtree is converted into a single leaf: code blocks, figures,
+ * table captions (which become a single paragraph before their table) and dates.
+ */
+pub fn is_converted_whole(element: &ElementRef) -> bool {
+    is_code_block(element) || is_figure(element) || element.value().name() == "caption" || is_date(element)
+}
+
+/**
+ * A <time> naming a day becomes a date node, in place of its text.
+ */
+fn is_date(element: &ElementRef) -> bool {
+    time_element_timestamp(element).is_some()
+}
+
+/**
+ * The single leaf an element converted as a whole becomes, see is_converted_whole.
+ */
+pub fn whole_element_leaf<'a>(node: NodeRef<'a, Node>, options: &ConverterOptions) -> DocNode<'a> {
+    let element = ElementRef::wrap(node).unwrap();
+    if is_figure(&element) {
+        return DocNode {
+            name: "figure",
+            text: "".to_owned(),
+            node,
+        };
+    }
+    if is_date(&element) {
+        return DocNode {
+            name: "time",
+            text: "".to_owned(),
+            node,
+        };
+    }
+    if element.value().name() == "caption" {
+        return DocNode {
+            name: "caption",
+            text: collapse_whitespace(&element.text().collect::<String>()).trim().to_string(),
+            node,
+        };
+    }
+    DocNode {
+        name: "pre",
+        text: code_block_text(&element, options),
+        node,
+    }
+}
+
+/**
+ * Collapse any run of whitespace into a single space, matching how browsers render
+ * text outside of <pre> elements. Only ASCII whitespace collapses, non-breaking spaces are kept.
+ */
+fn collapse_whitespace(text: &str) -> String {
+    lazy_static! {
+        static ref WHITESPACE: Regex = Regex::new(r"[ \t\n\r\x0C]+").unwrap();
+    }
+    WHITESPACE.replace_all(text, " ").to_string()
+}
+
+/**
+ * Text within a <q> is quoted as a browser renders it: the opening mark goes before the first text of
+ * the quote and the closing mark after its last, both within any surrounding whitespace.
+ * Quotes within quotes alternate between double and single quotation marks.
+ */
+fn add_quotation_marks(node: NodeRef<Node>, text: String) -> String {
+    let mut quotes: Vec<ElementRef> = node
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .filter(|element| element.value().name() == "q")
+        .collect();
+    if quotes.is_empty() {
+        return text;
+    }
+    quotes.reverse();
+    let (mut opening, mut closing) = (String::new(), String::new());
+    quotes.iter().enumerate().for_each(|(depth, quote)| {
+        let (open, close) = if depth % 2 == 0 { ('“', '”') } else { ('‘', '’') };
+        let mut texts = quote
+            .descendants()
+            .filter(|descendant| descendant.value().as_text().is_some_and(|text| !text.trim().is_empty()));
+        let is_first = texts.next().is_some_and(|first| first.id() == node.id());
+        if is_first {
+            opening.push(open);
+        }
+        if texts.last().map_or(is_first, |last| last.id() == node.id()) {
+            closing.insert(0, close);
+        }
+    });
+    let start = text.len() - text.trim_start().len();
+    let end = text.trim_end().len().max(start);
+    format!("{}{opening}{}{closing}{}", &text[..start], &text[start..end], &text[end..])
+}
+
+/**
+ * As collapse_whitespace, but each line is collapsed separately and the line breaks themselves are kept.
+ * Line breaks that only indent the markup, at the very start or end of an element, are dropped.
+ */
+fn collapse_whitespace_keeping_line_breaks(node: NodeRef<Node>, text: &str) -> String {
+    let lines: Vec<String> = text.split('\n').map(collapse_whitespace).collect();
+    let last = lines.len() - 1;
+    let mut text = lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| match (index, line) {
+            (0, line) => line.trim_end(),
+            (index, line) if index == last => line.trim_start(),
+            (_, line) => line.trim(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    if last > 0 && node.prev_sibling().is_none() {
+        text = text.trim_start_matches('\n').to_string();
+    }
+    if last > 0 && node.next_sibling().is_none() {
+        text = text.trim_end_matches('\n').to_string();
+    }
+    text
+}
+
+/**
+ * Collapsing whitespace continues across inline elements: a browser renders "A <b> B</b>" with a
+ * single space. Returns true if the text preceding the node, within the same run of inline content,
+ * ends with whitespace, so that any whitespace the node starts with collapses into it.
+ */
+fn follows_whitespace(node: NodeRef<Node>, options: &ConverterOptions) -> bool {
+    let mut current = node;
+    loop {
+        match current.prev_sibling() {
+            Some(previous) => {
+                if let Some(ends_with_whitespace) = trailing_whitespace(previous, options) {
+                    return ends_with_whitespace;
                 }
+                current = previous;
             }
-            _ => false, // Ignore comments, doctypes etc.
+            None => match current.parent().and_then(ElementRef::wrap) {
+                Some(parent) if is_inline(&parent) => current = *parent,
+                _ => return false,
+            },
         }
+    }
+}
+
+/**
+ * For follows_whitespace: whether the last text of a node ends with whitespace, false if the node
+ * ends the run of inline content, and None if it has no text of its own to tell.
+ */
+fn trailing_whitespace(node: NodeRef<Node>, options: &ConverterOptions) -> Option<bool> {
+    match node.value() {
+        Node::Text(text) if text.trim().is_empty() && !keeps_blank_text(node) => None,
+        Node::Text(text) => Some(text.ends_with(char::is_whitespace)),
+        Node::Element(_) => {
+            let element = ElementRef::wrap(node)?;
+            if excludes_subtree(&element, options) {
+                None
+            } else if is_inline(&element) {
+                node.children().rev().find_map(|child| trailing_whitespace(child, options))
+            } else {
+                Some(false)
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_inline(element: &ElementRef) -> bool {
+    static INLINE_ELEMENTS: [&str; 30] = [
+        "a", "abbr", "b", "bdi", "bdo", "cite", "code", "data", "del", "dfn", "em", "font", "i", "ins", "kbd",
+        "label", "mark", "q", "s", "samp", "small", "span", "strike", "strong", "sub", "sup", "time", "tt", "u",
+        "var",
+    ];
+    INLINE_ELEMENTS.contains(&element.value().name())
+}
+
+/**
+ * Whitespace-only text is only significant when it sits inside an inline or paragraph-like
+ * container. Between list items, table rows, headings or at the document root it is dropped.
+ * Within a heading or list item it still separates two inline siblings, as in "<h1><b>A</b> <i>B</i></h1>".
+ */
+fn keeps_blank_text(node: NodeRef<Node>) -> bool {
+    static STRUCTURAL_PARENTS: [&str; 16] = [
+        "html", "body", "ul", "ol", "li", "table", "thead", "tbody", "tfoot", "tr", "h1", "h2",
+        "h3", "h4", "h5", "h6",
+    ];
+    static INLINE_CONTAINERS: [&str; 7] = ["li", "h1", "h2", "h3", "h4", "h5", "h6"];
+    let parent = match node.parent().and_then(ElementRef::wrap) {
+        Some(parent) => parent,
+        None => return false,
+    };
+    if !STRUCTURAL_PARENTS.contains(&parent.value().name()) {
+        return true;
+    }
+    let is_inline_sibling = |sibling: Option<NodeRef<Node>>| {
+        sibling.is_some_and(|sibling| match ElementRef::wrap(sibling) {
+            Some(element) => is_inline(&element),
+            None => sibling.value().is_text(),
+        })
+    };
+    INLINE_CONTAINERS.contains(&parent.value().name())
+        && is_inline_sibling(node.prev_sibling())
+        && is_inline_sibling(node.next_sibling())
+}
+
+/**
+ * Returns the tag name for an element, restoring any placeholder names we introduced.
+ */
+pub fn element_name<'a>(element: &ElementRef<'a>) -> &'a str {
+    match element.value().name() {
+        name if name == HRBR_PLACEHOLDER => "hr",
+        name => name,
+    }
+}
+
+/**
+ * Returns true if the given element is not permitted by our options, and should be unwrapped
+ * (contribute nothing itself, while its children are still converted).
+ */
+pub fn is_unwrapped(element: &ElementRef, options: &ConverterOptions) -> bool {
+    is_editor_wrapper(element, options)
+        || (options.unknown_element_policy == UnknownElementPolicy::Unwrap
+            && !options.is_tag_allowed(element_name(element)))
+}
+
+fn is_editor_wrapper(element: &ElementRef, options: &ConverterOptions) -> bool {
+    options.editor_wrappers.iter().any(|wrapper| wrapper.matches(element))
+}
+
+/**
+ * Returns true if the node, or any of its ancestors, causes its whole subtree
+ * to be excluded from our document.
+ */
+pub fn is_excluded(node: NodeRef<Node>, options: &ConverterOptions) -> bool {
+    std::iter::once(node)
+        .chain(node.ancestors())
+        .filter_map(ElementRef::wrap)
+        .any(|element| excludes_subtree(&element, options))
+}
+
+/**
+ * The number of elements excluded from our document, either themselves or through an ancestor.
+ */
+pub fn count_excluded(fragment: &Html, options: &ConverterOptions) -> usize {
+    fragment
+        .tree
+        .nodes()
+        .filter(|node| node.value().is_element() && is_excluded(*node, options))
+        .count()
+}
+
+/**
+ * Returns true if the given element excludes itself and its whole subtree from our document.
+ */
+pub fn excludes_subtree(element: &ElementRef, options: &ConverterOptions) -> bool {
+    // Never content: scripts and styles are code, selects have no text of their own, template content
+    // is inert and noscript holds fallback markup (which the parser keeps as raw text).
+    // These are excluded whatever the allowlist says.
+    static EXCLUDED: [&str; 6] = ["head", "script", "style", "select", "template", "noscript"];
+    static FORM_FIELDS: [&str; 4] = ["input", "textarea", "button", "select"];
+    let name = element.value().name();
+    EXCLUDED.contains(&name)
+        || (options.exclude_aria_hidden
+            && element.value().attr("aria-hidden").is_some_and(|hidden| hidden.trim().eq_ignore_ascii_case("true")))
+        || (options.exclude_display_none && is_display_none(element))
+        || (options.form_field_policy == FormFieldPolicy::Drop && FORM_FIELDS.contains(&name))
+        || is_expand_title(element)
+        || (options.unknown_element_policy == UnknownElementPolicy::Drop
+            && !options.is_tag_allowed(element_name(element))
+            && !is_editor_wrapper(element, options))
+}
+
+/**
+ * Returns true for an element styled `display: none` inline.
+ */
+fn is_display_none(element: &ElementRef) -> bool {
+    extract_styles(element).is_some_and(|styles| {
+        styles.iter().any(|style| {
+            style[0].eq_ignore_ascii_case("display")
+                && style[1].trim_end_matches("!important").trim().eq_ignore_ascii_case("none")
+        })
     })
 }
 
-// Helper function to check if a node is inside a <pre> element
-fn is_inside_pre(node: NodeRef<Node>) -> bool {
-    node.ancestors().any(|ancestor| {
-        if let Some(element) = ancestor.value().as_element() {
-            element.name() == "pre"
+/**
+ * Returns true for the <summary> of a <details>, which becomes the title of its expand rather than content.
+ */
+fn is_expand_title(element: &ElementRef) -> bool {
+    element.value().name() == "summary"
+        && element
+            .parent()
+            .and_then(ElementRef::wrap)
+            .is_some_and(|parent| parent.value().name() == "details")
+}
+
+/**
+ * The text an <input> contributes: its value, or failing that its placeholder.
+ * Only inputs holding free text are considered (not checkboxes, passwords etc).
+ */
+fn input_text(element: &ElementRef) -> Option<String> {
+    static TEXT_INPUT_TYPES: [&str; 6] = ["text", "search", "email", "url", "tel", "number"];
+    let input_type = element.value().attr("type").unwrap_or("text").to_ascii_lowercase();
+    if !TEXT_INPUT_TYPES.contains(&input_type.as_str()) {
+        return None;
+    }
+    ["value", "placeholder"]
+        .iter()
+        .filter_map(|name| element.value().attr(name))
+        .map(|text| collapse_whitespace(text.trim()))
+        .find(|text| !text.is_empty())
+}
+
+/**
+ * Returns the leaf for a node of our parsed HTML (once the node and its children have been
+ * visited), or None if the node does not produce a leaf itself.
+ * inside_pre tells whether a text node sits inside a <pre> element (or another element preserving whitespace).
+ */
+pub fn leaf_for_node<'a>(
+    node: NodeRef<'a, Node>,
+    inside_pre: bool,
+    options: &ConverterOptions,
+) -> Option<DocNode<'a>> {
+    if let Some(element) = ElementRef::wrap(node) {
+        if is_unwrapped(&element, options) {
+            return None;
+        }
+        let name = element.value().name();
+        // Handle self-closing or special leaf nodes
+        if name == "iframe" || name == "img" {
+            Some(DocNode {
+                name: name.trim(), // Use the actual name
+                text: "".to_owned(), // No text content for these
+                node,
+            })
+        } else if name == HRBR_PLACEHOLDER {
+            Some(DocNode {
+                name: "hr", // Restore original name
+                text: "".to_owned(),
+                node,
+            })
+        } else if name == "br" {
+            Some(DocNode {
+                name: "br",
+                text: "".to_owned(),
+                node,
+            })
+        } else if name == "input" {
+            input_text(&element).map(|text| DocNode {
+                name: "text",
+                text,
+                node,
+            })
+        } else if name == "td" && !has_text_node(node, options) {
+            // Add TD node only if it's genuinely empty (doesn't contain significant text nodes)
+            Some(DocNode {
+                name: "td",
+                text: "".to_owned(),
+                node,
+            })
         } else {
-            false
+            // Other closing tags like </font>, </p>, </li> etc. are handled implicitly
+            // by the traversal and the text node logic below.
+            None
         }
-    })
+    } else if let Node::Text(text_node) = node.value() {
+        // Only consider text nodes that have a parent element. The text of an iframe is
+        // fallback markup for browsers without iframes, the iframe itself becomes our card.
+        let parent = node.parent()?;
+        if parent.value().as_element().is_some_and(|parent| parent.name() == "iframe") {
+            return None;
+        }
+        let text_content = &text_node.text;
+
+        // Inside <pre> we preserve all whitespace, elsewhere runs of whitespace
+        // collapse to a single space as they would when rendered.
+        let mut text = if inside_pre {
+            text_content.to_string()
+        } else if options.preserve_line_breaks_in_paragraphs {
+            collapse_whitespace_keeping_line_breaks(node, text_content)
+        } else {
+            collapse_whitespace(text_content)
+        };
+        if !inside_pre && text.starts_with(' ') && follows_whitespace(node, options) {
+            text.remove(0);
+            if text.is_empty() {
+                return None;
+            }
+        }
+
+        // Whitespace-only text between structural elements carries no content.
+        let should_keep = if inside_pre {
+            !text.is_empty()
+        } else if text.trim().is_empty() {
+            keeps_blank_text(node)
+        } else {
+            true
+        };
+
+        should_keep.then(|| DocNode {
+            name: "text",
+            text: add_quotation_marks(node, text),
+            node,
+        })
+    } else {
+        None
+    }
 }
 
 /**
@@ -60,74 +659,42 @@ fn is_inside_pre(node: NodeRef<Node>) -> bool {
  * list of leaf doc nodes  (each with a linked list pointer to the root)
  * for us to attempt to transform into an ADF Document
  */
-pub fn extract_leaves(fragment: &Html) -> Vec<DocNode> {
+pub fn extract_leaves<'a>(fragment: &'a Html, options: &ConverterOptions) -> Vec<DocNode<'a>> {
     let mut leaf_nodes: Vec<DocNode> = Vec::new();
+    // The element converted as a whole (such as a code block) we are currently inside of. Its subtree
+    // becomes a single leaf, so we decide once when entering it and skip everything until it closes.
+    let mut whole_element: Option<NodeId> = None;
     fragment
         .root_element()
         .traverse()
         .for_each(|edge| match edge {
             Edge::Close(node) => {
-                if let Some(element) = ElementRef::wrap(node) {
-                    let name = element.value().name();
-                    // Handle self-closing or special leaf nodes
-                    if name == "iframe" || name == "img" {
-                        leaf_nodes.push(DocNode {
-                            name: name.trim(), // Use the actual name
-                            text: "".to_owned(), // No text content for these
-                            node,
-                        })
-                    } else if name == HRBR_PLACEHOLDER {
-                        leaf_nodes.push(DocNode {
-                            name: "hr", // Restore original name
-                            text: "".to_owned(),
-                            node,
-                        })
-                    } else if name == "br" {
-                         leaf_nodes.push(DocNode {
-                            name: "br",
-                            text: "".to_owned(),
-                            node,
-                        })
-                    } else if name == "td" {
-                        // Add TD node only if it's genuinely empty (doesn't contain significant text nodes)
-                        if !has_text_node(node) {
-                            leaf_nodes.push(DocNode {
-                                name: "td",
-                                text: "".to_owned(),
-                                node,
-                            })
-                        }
-                    }
-                    // Other closing tags like </font>, </p>, </li> etc. are handled implicitly
-                    // by the traversal and the text node logic below.
-
-                } else if let Node::Text(text_node) = node.value() {
-                    // Only consider text nodes that have a parent element
-                    if node.parent().is_some() {
-                        let text_content = &text_node.text;
-                        let inside_pre = is_inside_pre(node);
-
-                        // Determine if this text node should be kept
-                        let should_keep = if inside_pre {
-                            // Inside <pre>: Keep if it's not completely empty. Preserve all whitespace.
-                            !text_content.is_empty()
-                        } else {
-                            // Outside <pre>: Keep only if it contains non-whitespace characters.
-                            !text_content.trim().is_empty()
-                        };
-
-                        if should_keep {
-                            leaf_nodes.push(DocNode {
-                                name: "text",
-                                // IMPORTANT: Always store the *original* text content.
-                                text: text_content.to_string(),
-                                node,
-                            })
+                if let Some(whole_element_id) = whole_element {
+                    if node.id() == whole_element_id {
+                        whole_element = None;
+                        if !is_excluded(node, options) {
+                            leaf_nodes.push(whole_element_leaf(node, options))
                         }
                     }
+                    return;
+                }
+                if is_excluded(node, options) {
+                    return;
+                }
+                let inside_pre = node.value().is_text() && is_inside_pre(node);
+                if let Some(leaf) = leaf_for_node(node, inside_pre, options) {
+                    leaf_nodes.push(leaf)
+                }
+            }
+            Edge::Open(node) => {
+                if whole_element.is_none()
+                    && ElementRef::wrap(node).is_some_and(|element| {
+                        !is_unwrapped(&element, options) && is_converted_whole(&element)
+                    })
+                {
+                    whole_element = Some(node.id());
                 }
             }
-            Edge::Open(_) => (),
         });
     leaf_nodes
 }
@@ -1,12 +1,57 @@
 mod adf_builder;
 mod adf_structure;
+mod colors;
+mod dates;
 mod extractor;
+mod single_pass;
+mod storage_format;
 mod tests;
 mod types;
+mod urls;
+mod warnings;
 
 extern crate wasm_bindgen;
 
+pub use adf_builder::assemble;
+pub use adf_builder::convert_document;
+pub use adf_builder::convert_fragment;
+pub use adf_builder::convert_many;
+pub use adf_builder::convert_many_with_options;
+pub use adf_builder::try_convert_html_str_to_adf_str;
+pub use adf_builder::marks_for_element;
+pub use adf_builder::doc_node_to_value;
 pub use adf_builder::convert_html_str_to_adf_str;
+pub use adf_builder::convert_html_str_to_adf_str_with_options;
+pub use adf_builder::convert_html_str_to_adf_str_with_warnings;
+pub use adf_builder::convert_html_str_to_adf_str_with_stats;
+pub use extractor::extract_leaves;
+pub use extractor::parse_html;
+pub use single_pass::convert_html_str_to_adf_str_single_pass;
+pub use storage_format::convert_html_str_to_storage_format;
+pub use storage_format::to_storage_format;
+pub use types::adf_content_type::{AdfContentType, AdfMark, AdfMarkAttributes};
+pub use types::conversion_stats::ConversionStats;
+pub use types::conversion_warning::{ConversionWarning, SourcePosition};
+pub use types::converter_options::AttributeHook;
+pub use types::converter_options::CellAccessibilityPolicy;
+pub use types::converter_options::ConverterOptions;
+pub use types::converter_options::EditMetadataPolicy;
+pub use types::converter_options::FormFieldPolicy;
+pub use types::converter_options::ListItemValuePolicy;
+pub use types::converter_options::MarkOrder;
+pub use types::converter_options::ReversedListPolicy;
+pub use types::converter_options::TableSummaryPolicy;
+pub use types::converter_options::UnknownElementPolicy;
+pub use types::doc_node::DocNode;
+pub use types::html_to_adf_error::HtmlToAdfError;
+pub use types::node_transformer::{NodeTransformer, TransformResult};
+// DocNode (and the parsed Html it points into) is built on these crates. They are re-exported
+// so that custom assembly does not need to pin matching versions of them.
+pub use ego_tree;
+pub use scraper;
+// The base URL option is a url::Url, and panel classes are matched with regex::Regex
+pub use regex;
+pub use url;
 use std::{
     ffi::{CStr, CString},
     os::raw::c_char,
@@ -0,0 +1,153 @@
+use std::borrow::Cow;
+
+use ego_tree::iter::Edge;
+use ego_tree::NodeId;
+use scraper::{ElementRef, Html};
+use serde_json::Value;
+
+use crate::adf_builder::{
+    assemble_leaf, document_json, exceeds_node_limit, finish_document, is_assembled, node_attributes, push_element_marks,
+    push_path_node, remove_illegal_marks,
+};
+use crate::extractor;
+use crate::types::adf_content_type::{transformed_content_type, AdfContentType};
+use crate::types::converter_options::ConverterOptions;
+use crate::types::doc_node::DocNode;
+use crate::types::node_list::{NodeHandle, NodeList};
+
+/**
+ * An element we are currently inside of.
+ * Everything a leaf needs to know about its ancestors is worked out once, as we enter the element.
+ */
+struct OpenElement<'a> {
+    element: ElementRef<'a>,
+    content_type: Cow<'static, AdfContentType>,
+    /// The attributes of the node this element becomes.
+    attributes: Vec<(String, Value)>,
+    /// The marks introduced by this element and all of its ancestors.
+    marks: Vec<Value>,
+    unwrapped: bool,
+    inside_pre: bool,
+}
+
+/// Convert an HTML string to an ADF string, in a single pass over the parsed HTML.
+///
+/// The output is identical to [`convert_html_str_to_adf_str_with_options`](crate::convert_html_str_to_adf_str_with_options).
+/// Rather than collecting all leaves first and then walking up from each leaf to the root,
+/// we keep a stack of the elements we are inside of, and assemble each leaf as we reach it.
+///
+/// One exception: for some misnested HTML the parser moves nodes around, and ego_tree 0.6 can leave
+/// a moved node pointing at its old parent. The leaf based path walks those parent links, while
+/// this path follows the structure of the tree as it is traversed.
+///
+/// ```rust
+/// use htmltoadf::{convert_html_str_to_adf_str_single_pass, ConverterOptions};
+///
+/// let converted = convert_html_str_to_adf_str_single_pass("<h1>Hello</h1>".to_string(), &ConverterOptions::default());
+/// assert!(converted.contains(r#""type":"heading""#));
+/// ```
+pub fn convert_html_str_to_adf_str_single_pass(html: String, options: &ConverterOptions) -> String {
+    let fragment = extractor::parse_html(html);
+    document_json(&build_adf_doc(&fragment, options), options)
+}
+
+fn build_adf_doc(fragment: &Html, options: &ConverterOptions) -> NodeList {
+    let mut node_list = NodeList::default();
+    let mut current_paragraph_handle: NodeHandle = 0;
+    let mut open_elements: Vec<OpenElement> = vec![];
+    // While inside an excluded subtree, or an element converted as a whole (e.g. a code block), nothing else is visited
+    let mut skipped_depth = 0;
+    let mut whole_element: Option<NodeId> = None;
+
+    fragment.root_element().traverse().for_each(|edge| match edge {
+        Edge::Open(node) => {
+            if skipped_depth > 0 {
+                skipped_depth += 1;
+                return;
+            }
+            let element = match ElementRef::wrap(node) {
+                Some(element) => element,
+                None => return,
+            };
+            let unwrapped = extractor::is_unwrapped(&element, options);
+            if extractor::excludes_subtree(&element, options) {
+                skipped_depth = 1;
+                return;
+            }
+            if !unwrapped && extractor::is_converted_whole(&element) {
+                skipped_depth = 1;
+                whole_element = Some(node.id());
+                return;
+            }
+
+            let parent = open_elements.last();
+            let content_type = transformed_content_type(&element, options);
+            let mut marks = parent.map(|parent| parent.marks.clone()).unwrap_or_default();
+            let mut attributes = vec![];
+            if !unwrapped {
+                push_element_marks(&mut marks, &content_type, &element, options);
+                attributes = node_attributes(&content_type, &element, options);
+            }
+            let inside_pre = parent.is_some_and(|parent| parent.inside_pre) || extractor::preserves_whitespace(element.value());
+            open_elements.push(OpenElement {
+                element,
+                content_type,
+                attributes,
+                marks,
+                unwrapped,
+                inside_pre,
+            });
+        }
+        Edge::Close(node) => {
+            if skipped_depth > 0 {
+                skipped_depth -= 1;
+                if skipped_depth == 0 && whole_element == Some(node.id()) {
+                    whole_element = None;
+                    let leaf = extractor::whole_element_leaf(node, options);
+                    push_leaf(&leaf, &open_elements, &mut node_list, &mut current_paragraph_handle, options);
+                }
+                return;
+            }
+            if node.value().is_element() {
+                open_elements.pop();
+            }
+            let inside_pre = open_elements.last().is_some_and(|parent| parent.inside_pre);
+            if let Some(leaf) = extractor::leaf_for_node(node, inside_pre, options) {
+                push_leaf(&leaf, &open_elements, &mut node_list, &mut current_paragraph_handle, options);
+            }
+        }
+    });
+    finish_document(&fragment.tree, &mut node_list, options);
+    node_list
+}
+
+/**
+ * Assemble a leaf beneath the elements we are currently inside of.
+ */
+fn push_leaf(
+    leaf: &DocNode,
+    open_elements: &[OpenElement],
+    node_list: &mut NodeList,
+    current_paragraph_handle: &mut NodeHandle,
+    options: &ConverterOptions,
+) {
+    if !is_assembled(leaf) || exceeds_node_limit(node_list, options) {
+        return;
+    }
+    let mut parent = 0;
+    for open_element in open_elements.iter().filter(|open_element| !open_element.unwrapped) {
+        parent = push_path_node(
+            &open_element.element,
+            &open_element.content_type,
+            parent,
+            node_list,
+            || open_element.attributes.clone(),
+        );
+    }
+    let mut marks = open_elements
+        .last()
+        .map(|parent| parent.marks.clone())
+        .unwrap_or_default();
+    remove_illegal_marks(&mut marks);
+    assemble_leaf(leaf, parent, marks, node_list, current_paragraph_handle, options);
+}
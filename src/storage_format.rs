@@ -0,0 +1,218 @@
+use serde_json::Value;
+
+use crate::adf_builder::convert_html_str_to_adf_value;
+use crate::dates::timestamp_date;
+use crate::types::converter_options::ConverterOptions;
+
+/// Convert an HTML string to the Confluence storage format (XHTML), using the given options.
+///
+/// The HTML is converted exactly as for ADF, and the resulting document is then written out
+/// as storage format, see [`to_storage_format`].
+///
+/// ```rust
+/// use htmltoadf::{convert_html_str_to_storage_format, ConverterOptions};
+///
+/// let converted = convert_html_str_to_storage_format("<h1>Hello <b>World</b></h1>".to_string(), &ConverterOptions::default());
+/// assert_eq!(converted, "<h1>Hello <strong>World</strong></h1>");
+/// ```
+pub fn convert_html_str_to_storage_format(html: String, options: &ConverterOptions) -> String {
+    to_storage_format(&convert_html_str_to_adf_value(html, options))
+}
+
+/// Write an ADF document, as produced by this crate, in the Confluence storage format.
+///
+/// Covers the nodes and marks this crate generates. Code blocks become code macros, expands expand macros,
+/// embed cards widget macros, emoji emoticons and dates `<time>` elements. Media referring to Confluence files
+/// have no storage format equivalent without their file name, so are left out, as are unknown nodes
+/// (their content is kept).
+///
+/// ```rust
+/// use htmltoadf::to_storage_format;
+/// use serde_json::json;
+///
+/// let adf = json!({
+///     "version": 1,
+///     "type": "doc",
+///     "content": [{"type": "rule"}]
+/// });
+/// assert_eq!(to_storage_format(&adf), "<hr />");
+/// ```
+pub fn to_storage_format(adf: &Value) -> String {
+    let mut storage = String::new();
+    write_node(adf, &mut storage);
+    storage
+}
+
+fn write_node(node: &Value, storage: &mut String) {
+    let attrs = &node["attrs"];
+    match node["type"].as_str().unwrap_or_default() {
+        "text" => write_text(node, storage),
+        "paragraph" => write_element("p", &[], node, storage),
+        "heading" => {
+            let level = attrs["level"].as_u64().unwrap_or(1).clamp(1, 6);
+            write_element(&format!("h{level}"), &[], node, storage)
+        }
+        "bulletList" => write_element("ul", &[], node, storage),
+        "orderedList" => match attrs["order"].as_u64().filter(|order| *order != 1) {
+            Some(order) => write_element("ol", &[("start", order.to_string())], node, storage),
+            None => write_element("ol", &[], node, storage),
+        },
+        "listItem" => write_element("li", &[], node, storage),
+        "blockquote" => write_element("blockquote", &[], node, storage),
+        "table" => {
+            storage.push_str("<table><tbody>");
+            write_content(node, storage);
+            storage.push_str("</tbody></table>");
+        }
+        "tableRow" => write_element("tr", &[], node, storage),
+        "tableCell" => write_element("td", &cell_attributes(attrs), node, storage),
+        "tableHeader" => write_element("th", &cell_attributes(attrs), node, storage),
+        "expand" => {
+            storage.push_str(r#"<ac:structured-macro ac:name="expand">"#);
+            if let Some(title) = attrs["title"].as_str() {
+                storage.push_str(&format!(r#"<ac:parameter ac:name="title">{}</ac:parameter>"#, escape(title)));
+            }
+            storage.push_str("<ac:rich-text-body>");
+            write_content(node, storage);
+            storage.push_str("</ac:rich-text-body></ac:structured-macro>");
+        }
+        "rule" => storage.push_str("<hr />"),
+        "hardBreak" => storage.push_str("<br />"),
+        "emoji" => {
+            storage.push_str("<ac:emoticon");
+            [("ac:emoji-shortname", "shortName"), ("ac:emoji-id", "id"), ("ac:emoji-fallback", "text")]
+                .iter()
+                .filter_map(|(name, attribute)| attrs[attribute].as_str().map(|value| (name, value)))
+                .for_each(|(name, value)| storage.push_str(&format!(r#" {name}="{}""#, escape(value))));
+            storage.push_str(" />");
+        }
+        "date" => {
+            if let Some(timestamp) = attrs["timestamp"].as_str().and_then(|timestamp| timestamp.parse().ok()) {
+                storage.push_str(&format!(r#"<time datetime="{}" />"#, timestamp_date(timestamp)));
+            }
+        }
+        "codeBlock" => write_code_block(node, storage),
+        "embedCard" => {
+            if let Some(url) = attrs["url"].as_str() {
+                storage.push_str(&format!(
+                    r#"<ac:structured-macro ac:name="widget"><ac:parameter ac:name="url"><ri:url ri:value="{}" /></ac:parameter></ac:structured-macro>"#,
+                    escape(url)
+                ));
+            }
+        }
+        "media" => {
+            if let (Some("external"), Some(url)) = (attrs["type"].as_str(), attrs["url"].as_str()) {
+                let alt = match attrs["alt"].as_str() {
+                    Some(alt) => format!(r#" ac:alt="{}""#, escape(alt)),
+                    None => "".to_string(),
+                };
+                let image = format!(r#"<ac:image{alt}><ri:url ri:value="{}" /></ac:image>"#, escape(url));
+                let link = node["marks"]
+                    .as_array()
+                    .and_then(|marks| marks.iter().find(|mark| mark["type"] == "link"))
+                    .and_then(|link| link["attrs"]["href"].as_str());
+                match link {
+                    Some(href) => storage.push_str(&format!(r#"<a href="{}">{image}</a>"#, escape(href))),
+                    None => storage.push_str(&image),
+                }
+            }
+        }
+        // doc, mediaSingle, mediaGroup and anything unknown only contribute their content
+        _ => write_content(node, storage),
+    }
+}
+
+fn write_content(node: &Value, storage: &mut String) {
+    if let Some(content) = node["content"].as_array() {
+        content.iter().for_each(|child| write_node(child, storage));
+    }
+}
+
+fn write_element(name: &str, attributes: &[(&str, String)], node: &Value, storage: &mut String) {
+    storage.push('<');
+    storage.push_str(name);
+    attributes.iter().for_each(|(key, value)| {
+        storage.push_str(&format!(r#" {key}="{}""#, escape(value)));
+    });
+    storage.push('>');
+    write_content(node, storage);
+    storage.push_str(&format!("</{name}>"));
+}
+
+fn cell_attributes(attrs: &Value) -> Vec<(&'static str, String)> {
+    ["colspan", "rowspan"]
+        .iter()
+        .filter_map(|name| attrs[name].as_u64().map(|span| (*name, span.to_string())))
+        .collect()
+}
+
+/**
+ * Code blocks become the code macro, with their text as a CDATA section.
+ * A CDATA section cannot contain "]]>", so any occurrence is split over two sections.
+ */
+fn write_code_block(node: &Value, storage: &mut String) {
+    storage.push_str(r#"<ac:structured-macro ac:name="code">"#);
+    if let Some(language) = node["attrs"]["language"].as_str() {
+        storage.push_str(&format!(
+            r#"<ac:parameter ac:name="language">{}</ac:parameter>"#,
+            escape(language)
+        ));
+    }
+    let text: String = node["content"]
+        .as_array()
+        .map(|content| content.iter().filter_map(|text| text["text"].as_str()).collect())
+        .unwrap_or_default();
+    storage.push_str(&format!(
+        "<ac:plain-text-body><![CDATA[{}]]></ac:plain-text-body></ac:structured-macro>",
+        text.replace("]]>", "]]]]><![CDATA[>")
+    ));
+}
+
+/**
+ * Text is wrapped in an element for each of its marks, the first mark outermost.
+ * Marks without a storage format equivalent (such as annotations) are left out.
+ */
+fn write_text(node: &Value, storage: &mut String) {
+    let marks: Vec<(String, String)> = node["marks"]
+        .as_array()
+        .map(|marks| marks.iter().filter_map(mark_tags).collect())
+        .unwrap_or_default();
+    marks.iter().for_each(|(open, _)| storage.push_str(open));
+    storage.push_str(&escape(node["text"].as_str().unwrap_or_default()));
+    marks.iter().rev().for_each(|(_, close)| storage.push_str(close));
+}
+
+/**
+ * The opening and closing tag for a mark.
+ */
+fn mark_tags(mark: &Value) -> Option<(String, String)> {
+    let tag = |name: &str| Some((format!("<{name}>"), format!("</{name}>")));
+    let attrs = &mark["attrs"];
+    match mark["type"].as_str()? {
+        "strong" => tag("strong"),
+        "em" => tag("em"),
+        "underline" => tag("u"),
+        "strike" => tag("s"),
+        "code" => tag("code"),
+        "subsup" => match attrs["type"].as_str()? {
+            "sub" => tag("sub"),
+            _ => tag("sup"),
+        },
+        "link" => Some((
+            format!(r#"<a href="{}">"#, escape(attrs["href"].as_str().unwrap_or_default())),
+            "</a>".to_string(),
+        )),
+        "textColor" => Some((
+            format!(r#"<span style="color: {}">"#, escape(attrs["color"].as_str()?)),
+            "</span>".to_string(),
+        )),
+        _ => None,
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
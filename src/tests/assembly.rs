@@ -0,0 +1,163 @@
+#[allow(unused_imports)]
+use crate::{assemble, doc_node_to_value, extract_leaves, parse_html, ConverterOptions, DocNode};
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[cfg(test)]
+#[test]
+fn custom_assembly() {
+    let options = ConverterOptions::default();
+    let html = parse_html("<p>Hello <b>world</b></p><hr/><p>Bye</p>".to_string());
+    let leaves: Vec<_> = extract_leaves(&html, &options)
+        .into_iter()
+        .filter(|leaf| leaf.name != "hr")
+        .map(|mut leaf| {
+            leaf.text = leaf.text.to_uppercase();
+            leaf
+        })
+        .collect();
+    assert_eq!(
+        assemble(leaves, &options),
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "HELLO "
+                        },
+                        {
+                            "type": "text",
+                            "text": "WORLD",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "BYE"
+                        }
+                    ]
+                }
+            ]
+        })
+    );
+}
+
+#[test]
+fn hand_built_leaves() {
+    let options = ConverterOptions::default();
+    let html = crate::scraper::Html::parse_fragment("<h2>Title</h2><p>Body</p>");
+    let leaves: Vec<DocNode> = html
+        .tree
+        .root()
+        .descendants()
+        .filter(|node: &crate::ego_tree::NodeRef<crate::scraper::Node>| node.value().is_text())
+        .map(|node| DocNode {
+            name: "text",
+            text: format!("[{}]", node.value().as_text().unwrap().trim()),
+            node,
+        })
+        .collect();
+    assert_eq!(
+        assemble(leaves, &options),
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "heading",
+                    "attrs": {
+                        "level": 2
+                    },
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "[Title]"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "[Body]"
+                        }
+                    ]
+                }
+            ]
+        })
+    );
+}
+
+#[test]
+fn leaf_ancestors() {
+    let html = parse_html(
+        "<table><tr><td><ul><li><blockquote><p><em><a href='#'>Deep</a></em></p></blockquote></li></ul></td></tr></table>"
+            .to_string(),
+    );
+    let leaves = extract_leaves(&html, &ConverterOptions::default());
+    assert_eq!(leaves.len(), 1);
+    assert_eq!(
+        leaves[0].ancestors_named().collect::<Vec<_>>(),
+        vec!["a", "em", "p", "blockquote", "li", "ul", "td", "tr", "tbody", "table", "html"]
+    );
+}
+
+#[test]
+fn text_leaf_to_value() {
+    let html = parse_html(r#"<p><a href="https://example.com"><b>Bold</b> link</a></p>"#.to_string());
+    let leaves = extract_leaves(&html, &ConverterOptions::default());
+    assert_eq!(
+        doc_node_to_value(&leaves[0]),
+        json!({
+            "type": "text",
+            "text": "Bold",
+            "marks": [
+                {
+                    "type": "link",
+                    "attrs": {
+                        "href": "https://example.com"
+                    }
+                },
+                {
+                    "type": "strong"
+                }
+            ]
+        })
+    );
+}
+
+#[test]
+fn image_leaf_to_value() {
+    let html = parse_html(r#"<p><img src="https://example.com/a.png" alt="A"></p>"#.to_string());
+    let leaves = extract_leaves(&html, &ConverterOptions::default());
+    assert_eq!(
+        doc_node_to_value(&leaves[0]),
+        json!({
+            "type": "mediaSingle",
+            "content": [
+                {
+                    "type": "media",
+                    "attrs": {
+                        "url": "https://example.com/a.png",
+                        "type": "external",
+                        "alt": "A"
+                    }
+                }
+            ]
+        })
+    );
+}
@@ -0,0 +1,123 @@
+#[allow(unused_imports)]
+use super::assert_output_json_eq;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[cfg(test)]
+#[test]
+fn blockquote() {
+    assert_output_json_eq(
+        "<blockquote>Quoted</blockquote>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "blockquote",
+              "content": [
+                {
+                  "type": "paragraph",
+                  "content": [
+                    {
+                      "type": "text",
+                      "text": "Quoted"
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
+
+#[test]
+fn blockquote_with_list() {
+    assert_output_json_eq(
+        "<blockquote><ul><li>One</li><li>Two</li></ul></blockquote>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "blockquote",
+              "content": [
+                {
+                  "type": "bulletList",
+                  "content": [
+                    {
+                      "type": "listItem",
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "One"
+                            }
+                          ]
+                        }
+                      ]
+                    },
+                    {
+                      "type": "listItem",
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "Two"
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
+
+#[test]
+fn blockquote_with_heading() {
+    assert_output_json_eq(
+        "<blockquote><h2>Title</h2><p>Body</p></blockquote>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "blockquote",
+              "content": [
+                {
+                  "type": "heading",
+                  "attrs": {
+                    "level": 2
+                  },
+                  "content": [
+                    {
+                      "type": "text",
+                      "text": "Title"
+                    }
+                  ]
+                },
+                {
+                  "type": "paragraph",
+                  "content": [
+                    {
+                      "type": "text",
+                      "text": "Body"
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
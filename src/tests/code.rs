@@ -0,0 +1,296 @@
+#[allow(unused_imports)]
+use super::{assert_output_json_eq, assert_output_json_eq_with_options};
+
+#[allow(unused_imports)]
+use crate::ConverterOptions;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[cfg(test)]
+#[test]
+fn code_block() {
+    assert_output_json_eq(
+        "<pre>let x = 1;\n  x + 1</pre>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "codeBlock",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "let x = 1;\n  x + 1"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn highlighted_code_block() {
+    assert_output_json_eq(
+        "<pre><code><span class=\"kw\">fn</span> <span style=\"color: #f00\">main</span>() {\n    <span class=\"mac\">println!</span>();\n}</code></pre>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "codeBlock",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "fn main() {\n    println!();\n}"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn markdown_inline_code() {
+    assert_output_json_eq_with_options(
+        "<p>Run `cargo build` then <b>`cargo test`</b>, but not ` this</p><p><code>`kept`</code></p>",
+        &ConverterOptions {
+            markdown_inline_code: true,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Run "
+                        },
+                        {
+                            "type": "text",
+                            "text": "cargo build",
+                            "marks": [
+                                {
+                                    "type": "code"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " then "
+                        },
+                        {
+                            "type": "text",
+                            "text": "cargo test",
+                            "marks": [
+                                {
+                                    "type": "code"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": ", but not ` this"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "`kept`",
+                            "marks": [
+                                {
+                                    "type": "code"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn markdown_inline_code_is_opt_in() {
+    assert_output_json_eq(
+        "<p>Run `cargo build`</p>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Run `cargo build`"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn code_within_pre_is_a_code_block() {
+    assert_output_json_eq(
+        r#"<p>Run <code>main</code>:</p><pre><code class="hljs language-rust">fn main() {}</code></pre>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Run "
+                        },
+                        {
+                            "type": "text",
+                            "text": "main",
+                            "marks": [
+                                {
+                                    "type": "code"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": ":"
+                        }
+                    ]
+                },
+                {
+                    "type": "codeBlock",
+                    "attrs": {
+                        "language": "rust"
+                    },
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "fn main() {}"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn code_block_language_sources() {
+    assert_output_json_eq(
+        r#"<pre class="language-rust" data-language="go">a</pre><pre data-language="go">b</pre><pre><code lang="js">c</code></pre><pre xml:lang="Python">d</pre><pre><code class="lang-ts">e</code></pre>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "codeBlock",
+                    "attrs": {
+                        "language": "rust"
+                    },
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "a"
+                        }
+                    ]
+                },
+                {
+                    "type": "codeBlock",
+                    "attrs": {
+                        "language": "go"
+                    },
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "b"
+                        }
+                    ]
+                },
+                {
+                    "type": "codeBlock",
+                    "attrs": {
+                        "language": "javascript"
+                    },
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "c"
+                        }
+                    ]
+                },
+                {
+                    "type": "codeBlock",
+                    "attrs": {
+                        "language": "python"
+                    },
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "d"
+                        }
+                    ]
+                },
+                {
+                    "type": "codeBlock",
+                    "attrs": {
+                        "language": "typescript"
+                    },
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "e"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn line_numbers_are_stripped() {
+    let code_block = |text: &str| {
+        json!({
+            "type": "codeBlock",
+            "content": [
+                {
+                    "type": "text",
+                    "text": text
+                }
+            ]
+        })
+    };
+    assert_output_json_eq_with_options(
+        concat!(
+            "<pre data-line-numbers>1 let x = 1;\n2   x + 1\n3\n</pre>",
+            "<pre><span class=\"linenos\">1</span>fn main() {\n<span class=\"linenos\">2</span>}</pre>",
+            "<pre data-line-numbers>10 apples\n3 pears</pre>"
+        ),
+        &ConverterOptions {
+            strip_line_numbers: true,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                code_block("let x = 1;\n  x + 1\n\n"),
+                code_block("fn main() {\n}"),
+                code_block("10 apples\n3 pears")
+            ]
+        }),
+    );
+}
@@ -1,6 +1,9 @@
 #[allow(unused_imports)]
 use super::assert_output_json_eq;
 
+#[allow(unused_imports)]
+use crate::{convert_html_str_to_adf_str, convert_html_str_to_adf_str_single_pass, ConverterOptions};
+
 #[allow(unused_imports)]
 use serde_json::json;
 
@@ -94,3 +97,107 @@ fn color_3() {
         }),
     );
 }
+
+#[cfg(test)]
+#[allow(dead_code)]
+fn text_color(color: &str) -> Option<String> {
+    let html = format!("<p style=\"color: {color}\">Paragraph</p>");
+    let converted = convert_html_str_to_adf_str(html.clone());
+    assert_eq!(converted, convert_html_str_to_adf_str_single_pass(html, &ConverterOptions::default()));
+    let adf: serde_json::Value = serde_json::from_str(&converted).unwrap();
+    adf["content"][0]["content"][0]["marks"][0]["attrs"]["color"]
+        .as_str()
+        .map(|color| color.to_string())
+}
+
+#[test]
+fn color_formats() {
+    let expectations = [
+        ("#332255", Some("#332255")),
+        ("#ABC", Some("#AABBCC")),
+        ("rgb(100, 200, 214)", Some("#64c8d6")),
+        ("RGB(0,0,0)", Some("#000000")),
+        ("rgb(300, 0, 0)", Some("#ff0000")),
+        ("red", Some("#ff0000")),
+        ("RebeccaPurple", Some("#663399")),
+        ("lightgoldenrodyellow", Some("#fafad2")),
+        ("grey", Some("#808080")),
+        ("navy !important", Some("#000080")),
+        ("#12345", None),
+        ("#1234567", None),
+        ("rgb(1, 2)", None),
+        ("transparent", None),
+        ("currentColor", None),
+        ("reddish", None),
+    ];
+    for (color, expected) in expectations {
+        assert_eq!(text_color(color).as_deref(), expected, "{color}");
+    }
+}
+
+#[test]
+fn font_color() {
+    assert_output_json_eq(
+        "<p><font color=\"teal\">Teal</font> <font color=\"teal\" style=\"color: #123456\">Styled</font></p>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Teal",
+                            "marks": [
+                                {
+                                    "type": "textColor",
+                                    "attrs": {
+                                        "color": "#008080"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "Styled",
+                            "marks": [
+                                {
+                                    "type": "textColor",
+                                    "attrs": {
+                                        "color": "#123456"
+                                    }
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn rgb_color_formats() {
+    let expectations = [
+        ("rgba(255, 0, 0, 0.5)", Some("#ff0000")),
+        ("rgba( 0 , 128 , 255 , 1 )", Some("#0080ff")),
+        ("rgb(100%, 50%, 0%)", Some("#ff8000")),
+        ("rgba(10%, 20%, 30%, 50%)", Some("#1a334d")),
+        ("rgb(12.4, 12.6, 0)", Some("#0c0d00")),
+        ("rgb(255 128 0)", Some("#ff8000")),
+        ("rgb(255 128 0 / 25%)", Some("#ff8000")),
+        ("rgba(255, 0, 0, 0)", None),
+        ("rgb(255 0 0 / 0%)", None),
+        ("rgba(255, 0, 0, 0.0)", None),
+        ("rgb(1.2.3, 0, 0)", None),
+        ("rgb(-1, 0, 0)", None),
+    ];
+    for (color, expected) in expectations {
+        assert_eq!(text_color(color).as_deref(), expected, "{color}");
+    }
+}
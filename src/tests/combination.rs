@@ -114,20 +114,6 @@ fn top_level() {
                                     "type": "paragraph",
                                     "content":
                                     [
-                                        {
-                                            "type": "text",
-                                            "text": " ",
-                                            "marks":
-                                            [
-                                                {
-                                                    "type": "textColor",
-                                                    "attrs":
-                                                    {
-                                                        "color": "#00FF00"
-                                                    }
-                                                }
-                                            ]
-                                        },
                                         {
                                             "type": "text",
                                             "text": "With some blue text inside",
@@ -141,20 +127,6 @@ fn top_level() {
                                                     }
                                                 }
                                             ]
-                                        },
-                                        {
-                                            "type": "text",
-                                            "text": " ",
-                                            "marks":
-                                            [
-                                                {
-                                                    "type": "textColor",
-                                                    "attrs":
-                                                    {
-                                                        "color": "#00FF00"
-                                                    }
-                                                }
-                                            ]
                                         }
                                     ]
                                 }
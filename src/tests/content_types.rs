@@ -0,0 +1,66 @@
+#[allow(unused_imports)]
+use crate::{parse_html, AdfContentType, AdfMark, AdfMarkAttributes};
+
+#[allow(unused_imports)]
+use scraper::{ElementRef, Selector};
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[cfg(test)]
+#[test]
+fn custom_content_type() {
+    let status = AdfContentType::from_name_and_marks(
+        "text",
+        &[AdfMark {
+            typename: "textColor".to_string(),
+            attributes: AdfMarkAttributes::Generator(|element| {
+                vec![("color".to_string(), element.value().attr("data-color").unwrap_or("#000000").to_string())]
+            }),
+        }],
+    );
+    let panel = AdfContentType::from_name_and_attributes("panel", |element| {
+        vec![("panelType".to_string(), json!(element.value().attr("data-type").unwrap_or("info")))]
+    });
+
+    let html = parse_html(r##"<div data-type="warning"><span data-color="#ff0000">Careful</span></div>"##.to_string());
+    let div = html.select(&Selector::parse("div").unwrap()).next().unwrap();
+    let span = html.select(&Selector::parse("span").unwrap()).next().unwrap();
+
+    assert_eq!(panel.typename, "panel");
+    assert_eq!((panel.attributes.unwrap())(&div), vec![("panelType".to_string(), json!("warning"))]);
+    assert_eq!(status.typename, "text");
+    match &status.marks[0].attributes {
+        AdfMarkAttributes::Generator(generator) => {
+            assert_eq!(generator(&span), vec![("color".to_string(), "#ff0000".to_string())])
+        }
+        AdfMarkAttributes::List(_) | AdfMarkAttributes::Map(_) => panic!("expected a generator"),
+    }
+}
+
+#[test]
+fn content_type_with_marks_and_attributes() {
+    let link = AdfContentType::from_name_and_marks_and_attributes(
+        "text",
+        &[AdfMark {
+            typename: "link".to_string(),
+            attributes: AdfMarkAttributes::Generator(|element| {
+                vec![("href".to_string(), element.value().attr("href").unwrap_or_default().to_string())]
+            }),
+        }],
+        |element| vec![("localId".to_string(), json!(element.value().attr("id")))],
+    );
+
+    let html = parse_html(r#"<p><a id="intro" href="https://example.com">Intro</a></p>"#.to_string());
+    let anchor = html.select(&Selector::parse("a").unwrap()).next().unwrap();
+
+    assert_eq!(link.typename, "text");
+    assert_eq!((link.attributes.unwrap())(&anchor), vec![("localId".to_string(), json!("intro"))]);
+    match &link.marks[0].attributes {
+        AdfMarkAttributes::Generator(generator) => assert_eq!(
+            generator(&anchor),
+            vec![("href".to_string(), "https://example.com".to_string())]
+        ),
+        AdfMarkAttributes::List(_) | AdfMarkAttributes::Map(_) => panic!("expected a generator"),
+    }
+}
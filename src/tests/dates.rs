@@ -0,0 +1,156 @@
+#[allow(unused_imports)]
+use super::assert_output_json_eq;
+
+#[allow(unused_imports)]
+use crate::{convert_html_str_to_storage_format, ConverterOptions};
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[test]
+fn time_with_date() {
+    assert_output_json_eq(
+        r#"<p>Due <time datetime="2024-05-01">May 1st</time></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Due "
+                        },
+                        {
+                            "type": "date",
+                            "attrs": {
+                                "timestamp": "1714521600000"
+                            }
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn time_with_date_time() {
+    // The date node holds the day as written, whatever the time and its zone
+    assert_output_json_eq(
+        r#"<p><time datetime="2024-02-29T23:30:00.5-05:00">Late</time> and <b><time>2024-02-29 09:30</time></b></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "date",
+                            "attrs": {
+                                "timestamp": "1709164800000"
+                            }
+                        },
+                        {
+                            "type": "text",
+                            "text": " and "
+                        },
+                        {
+                            "type": "date",
+                            "attrs": {
+                                "timestamp": "1709164800000"
+                            }
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn time_with_duration() {
+    assert_output_json_eq(
+        r#"<p>Takes <time datetime="PT1H30M">90 <b>minutes</b></time></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Takes "
+                        },
+                        {
+                            "type": "text",
+                            "text": "90 "
+                        },
+                        {
+                            "type": "text",
+                            "text": "minutes",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn time_without_a_day() {
+    // Invalid dates, times of day and months name no day either
+    assert_output_json_eq(
+        r#"<p><time datetime="2023-02-29">Leap</time> <time datetime="14:00">2pm</time> <time>2024-05</time></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Leap"
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "2pm"
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "2024-05"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn dates_in_storage_format() {
+    assert_eq!(
+        convert_html_str_to_storage_format(
+            r#"<p>From <time datetime="1969-12-31">New Year's Eve</time></p>"#.to_string(),
+            &ConverterOptions::default()
+        ),
+        r#"<p>From <time datetime="1969-12-31" /></p>"#
+    );
+}
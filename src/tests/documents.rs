@@ -0,0 +1,486 @@
+#[allow(unused_imports)]
+use super::{assert_output_json_eq, assert_output_json_eq_with_options};
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[allow(unused_imports)]
+use crate::{convert_document, convert_fragment, ConverterOptions, UnknownElementPolicy};
+
+#[allow(unused_imports)]
+use regex::Regex;
+
+#[allow(unused_imports)]
+use scraper::Selector;
+
+#[cfg(test)]
+#[allow(dead_code)]
+fn two_paragraphs() -> serde_json::Value {
+    json!({
+        "version": 1,
+        "type": "doc",
+        "content": [
+            {
+                "type": "paragraph",
+                "content": [
+                    {
+                        "type": "text",
+                        "text": "hi"
+                    }
+                ]
+            },
+            {
+                "type": "paragraph",
+                "content": [
+                    {
+                        "type": "text",
+                        "text": "bye"
+                    }
+                ]
+            }
+        ]
+    })
+}
+
+#[test]
+fn bare_fragment() {
+    assert_output_json_eq("<p>hi</p><p>bye</p>", two_paragraphs());
+}
+
+#[test]
+fn full_document() {
+    assert_output_json_eq(
+        "<!DOCTYPE html>
+        <html>
+            <head><title>Ignored</title></head>
+            <body><p>hi</p><p>bye</p></body>
+        </html>",
+        two_paragraphs(),
+    );
+}
+
+#[test]
+fn body_without_html() {
+    assert_output_json_eq("<body><p>hi</p><p>bye</p></body>", two_paragraphs());
+}
+
+#[test]
+fn document_version() {
+    assert_output_json_eq(
+        "<p>Hi</p>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Hi"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn document_version_and_metadata_can_be_set() {
+    assert_output_json_eq_with_options(
+        "<p>Hi</p>",
+        &ConverterOptions {
+            adf_version: 2,
+            doc_metadata: vec![
+                ("localId".to_string(), json!("page-1")),
+                ("type".to_string(), json!("not a doc")),
+            ],
+            ..Default::default()
+        },
+        json!({
+            "version": 2,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Hi"
+                        }
+                    ]
+                }
+            ],
+            "localId": "page-1"
+        }),
+    );
+}
+
+#[test]
+fn batch_conversion() {
+    let converted = crate::convert_many(&["<p>One</p>", "<p>Unclosed <b>bold</p></li></td>", "<p>Two</p>"]);
+    assert_eq!(converted.len(), 3);
+    assert_eq!(
+        converted[1],
+        Ok(json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Unclosed "
+                        },
+                        {
+                            "type": "text",
+                            "text": "bold",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }))
+    );
+    assert_eq!(converted[2].as_ref().unwrap()["content"][0]["content"][0]["text"], "Two");
+}
+
+#[test]
+fn pretty_json() {
+    let html = "<p>Hi</p>".to_string();
+    let options = ConverterOptions {
+        pretty_json: true,
+        ..Default::default()
+    };
+    let pretty = crate::convert_html_str_to_adf_str_with_options(html.clone(), &options);
+    assert_eq!(
+        pretty,
+        r#"{
+  "version": 1,
+  "type": "doc",
+  "content": [
+    {
+      "type": "paragraph",
+      "content": [
+        {
+          "type": "text",
+          "text": "Hi"
+        }
+      ]
+    }
+  ]
+}"#
+    );
+    assert_eq!(pretty, crate::convert_html_str_to_adf_str_single_pass(html.clone(), &options));
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&pretty).unwrap(),
+        serde_json::from_str::<serde_json::Value>(&crate::convert_html_str_to_adf_str(html)).unwrap()
+    );
+}
+
+#[test]
+fn output_node_limit() {
+    let html = "<ul><li><b>item</b></li></ul>".repeat(1000);
+    let options = ConverterOptions {
+        max_output_nodes: Some(100),
+        ..Default::default()
+    };
+    assert_eq!(
+        crate::try_convert_html_str_to_adf_str(html.clone(), &options),
+        Err(crate::HtmlToAdfError::TooLarge { limit: 100 })
+    );
+    assert_eq!(
+        crate::convert_many_with_options(&[&html, "<p>Small</p>"], &options)
+            .iter()
+            .map(Result::is_ok)
+            .collect::<Vec<_>>(),
+        vec![false, true]
+    );
+    // Elsewhere the document is cut short, in the same place for both assembly paths
+    let truncated = crate::convert_html_str_to_adf_str_with_options(html.clone(), &options);
+    assert!(truncated.len() < 10000);
+    assert_eq!(truncated, crate::convert_html_str_to_adf_str_single_pass(html.clone(), &options));
+    assert!(crate::try_convert_html_str_to_adf_str(html, &ConverterOptions::default()).is_ok());
+}
+
+#[test]
+fn trim_document() {
+    assert_output_json_eq_with_options(
+        "<br> <p>&nbsp;</p><p>One</p><p><br></p><p>Two</p><p> <br> </p>\n",
+        &ConverterOptions {
+            trim_document: true,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "One"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "hardBreak"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Two"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn editor_wrappers_are_unwrapped() {
+    let html = "<div class=\"ProseMirror\" contenteditable=\"true\" style=\"color: #172b4d\"><p>A <b>b</b></p><ul><li>B</li></ul></div>";
+    let expected = json!({
+        "version": 1,
+        "type": "doc",
+        "content": [
+            {
+                "type": "paragraph",
+                "content": [
+                    {
+                        "type": "text",
+                        "text": "A "
+                    },
+                    {
+                        "type": "text",
+                        "text": "b",
+                        "marks": [
+                            {
+                                "type": "strong"
+                            }
+                        ]
+                    }
+                ]
+            },
+            {
+                "type": "bulletList",
+                "content": [
+                    {
+                        "type": "listItem",
+                        "content": [
+                            {
+                                "type": "paragraph",
+                                "content": [
+                                    {
+                                        "type": "text",
+                                        "text": "B"
+                                    }
+                                ]
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    });
+    assert_output_json_eq(html, expected.clone());
+    // Wrappers are kept even where other unknown elements are dropped
+    assert_output_json_eq_with_options(
+        html,
+        &ConverterOptions {
+            allowed_tags: Some(["p", "b", "ul", "li"].iter().map(|tag| tag.to_string()).collect()),
+            unknown_element_policy: UnknownElementPolicy::Drop,
+            ..Default::default()
+        },
+        expected.clone(),
+    );
+    assert_output_json_eq_with_options(
+        &html.replace("ProseMirror\" contenteditable=\"true\"", "ql-editor\""),
+        &ConverterOptions {
+            editor_wrappers: vec![Selector::parse("div.ql-editor").unwrap()],
+            ..Default::default()
+        },
+        expected,
+    );
+}
+
+#[test]
+fn alert_divs_become_panels() {
+    assert_output_json_eq_with_options(
+        r#"<div class="alert alert-warning" role="alert">Mind the <b>gap</b></div><div class="alert alert-info"><p>Info</p></div><div class="alert">Plain</div>"#,
+        &ConverterOptions {
+            panel_classes: vec![
+                (Regex::new("warning").unwrap(), "warning".to_string()),
+                (Regex::new("^alert-(info|primary)$").unwrap(), "info".to_string()),
+            ],
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "panel",
+                    "attrs": {
+                        "panelType": "warning"
+                    },
+                    "content": [
+                        {
+                            "type": "paragraph",
+                            "content": [
+                                {
+                                    "type": "text",
+                                    "text": "Mind the "
+                                },
+                                {
+                                    "type": "text",
+                                    "text": "gap",
+                                    "marks": [
+                                        {
+                                            "type": "strong"
+                                        }
+                                    ]
+                                }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "type": "panel",
+                    "attrs": {
+                        "panelType": "info"
+                    },
+                    "content": [
+                        {
+                            "type": "paragraph",
+                            "content": [
+                                {
+                                    "type": "text",
+                                    "text": "Info"
+                                }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Plain"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn documents_leave_out_their_head() {
+    let html = "<title>Page</title><p>Body</p>";
+    assert_eq!(
+        convert_document(html.to_string(), &ConverterOptions::default()),
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Body"
+                        }
+                    ]
+                }
+            ]
+        })
+        .to_string()
+    );
+    // As a fragment there is no head, so the title is content like any other
+    assert_eq!(
+        convert_fragment(html.to_string(), &ConverterOptions::default()),
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Page"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Body"
+                        }
+                    ]
+                }
+            ]
+        })
+        .to_string()
+    );
+}
+
+#[test]
+fn documents_and_fragments_wrap_loose_content() {
+    let html = "<html><body>Loose <b>text</b><p>Para</p></body></html>";
+    let expected = json!({
+        "version": 1,
+        "type": "doc",
+        "content": [
+            {
+                "type": "paragraph",
+                "content": [
+                    {
+                        "type": "text",
+                        "text": "Loose "
+                    },
+                    {
+                        "type": "text",
+                        "text": "text",
+                        "marks": [
+                            {
+                                "type": "strong"
+                            }
+                        ]
+                    }
+                ]
+            },
+            {
+                "type": "paragraph",
+                "content": [
+                    {
+                        "type": "text",
+                        "text": "Para"
+                    }
+                ]
+            }
+        ]
+    })
+    .to_string();
+    assert_eq!(convert_document(html.to_string(), &ConverterOptions::default()), expected);
+    assert_eq!(convert_fragment(html.to_string(), &ConverterOptions::default()), expected);
+}
@@ -0,0 +1,146 @@
+#[allow(unused_imports)]
+use super::assert_output_json_eq;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[cfg(test)]
+#[test]
+fn titled_iframe() {
+    assert_output_json_eq(
+        "<iframe src='https://www.youtube.com/embed/abc' title='Launch video' aria-label='Video'></iframe>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "embedCard",
+              "attrs": {
+                "url": "https://www.youtube.com/embed/abc",
+                "layout": "center"
+              }
+            },
+            {
+              "type": "paragraph",
+              "content": [
+                {
+                  "type": "text",
+                  "text": "Launch video"
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
+
+#[test]
+fn labelled_iframe() {
+    assert_output_json_eq(
+        "<iframe src='https://www.youtube.com/embed/abc' aria-label='Video'></iframe>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "embedCard",
+              "attrs": {
+                "url": "https://www.youtube.com/embed/abc",
+                "layout": "center"
+              }
+            },
+            {
+              "type": "paragraph",
+              "content": [
+                {
+                  "type": "text",
+                  "text": "Video"
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
+
+#[test]
+fn untitled_iframe() {
+    assert_output_json_eq(
+        "<iframe src='https://www.youtube.com/embed/abc'></iframe>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "embedCard",
+              "attrs": {
+                "url": "https://www.youtube.com/embed/abc",
+                "layout": "center"
+              }
+            }
+          ]
+        }),
+    )
+}
+
+#[test]
+fn iframe_inside_list() {
+    assert_output_json_eq(
+        "<ul><li><iframe src='https://www.youtube.com/embed/abc' title='Launch video'></iframe></li></ul>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "bulletList",
+              "content": [
+                {
+                  "type": "listItem",
+                  "content": [
+                    {
+                      "type": "paragraph",
+                      "content": [
+                        {
+                          "type": "text",
+                          "text": "Launch video",
+                          "marks": [
+                            {
+                              "type": "link",
+                              "attrs": {
+                                "href": "https://www.youtube.com/embed/abc"
+                              }
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
+
+#[test]
+fn iframe_without_source() {
+    assert_output_json_eq(
+        "<iframe></iframe>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "paragraph",
+              "content": [
+                {
+                  "type": "text",
+                  "text": "External Content"
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
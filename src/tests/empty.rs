@@ -1,6 +1,9 @@
 #[cfg(test)]
 #[allow(unused_imports)]
-use super::assert_output_json_eq;
+use super::{assert_output_json_eq, assert_output_json_eq_with_options};
+
+#[allow(unused_imports)]
+use crate::ConverterOptions;
 
 #[allow(unused_imports)]
 use serde_json::json;
@@ -12,7 +15,11 @@ fn empty() {
         json!({
             "version": 1,
             "type": "doc",
-            "content": []
+            "content": [
+                {
+                    "type": "paragraph"
+                }
+            ]
         }),
     );
 }
@@ -21,6 +28,42 @@ fn empty() {
 fn html_only() {
     assert_output_json_eq(
         "<html></html>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph"
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn whitespace_only() {
+    assert_output_json_eq(
+        " \n\t ",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph"
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn empty_document_without_paragraph() {
+    assert_output_json_eq_with_options(
+        "",
+        &ConverterOptions {
+            empty_document_paragraph: false,
+            ..Default::default()
+        },
         json!({
             "version": 1,
             "type": "doc",
@@ -0,0 +1,115 @@
+#[allow(unused_imports)]
+use super::{assert_output_json_eq, assert_output_json_eq_with_options};
+
+#[allow(unused_imports)]
+use crate::ConverterOptions;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[cfg(test)]
+#[test]
+fn details_become_an_expand() {
+    assert_output_json_eq(
+        "<details><summary>Title</summary><p>Hidden</p></details>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "expand",
+              "attrs": {
+                "title": "Title"
+              },
+              "content": [
+                {
+                  "type": "paragraph",
+                  "content": [
+                    {
+                      "type": "text",
+                      "text": "Hidden"
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
+
+#[test]
+fn rich_summary_is_flattened() {
+    assert_output_json_eq(
+        "<details><summary><b>Bold</b> title<br></summary>Hidden <i>text</i></details>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "expand",
+              "attrs": {
+                "title": "Bold title"
+              },
+              "content": [
+                {
+                  "type": "paragraph",
+                  "content": [
+                    {
+                      "type": "text",
+                      "text": "Hidden "
+                    },
+                    {
+                      "type": "text",
+                      "text": "text",
+                      "marks": [
+                        {
+                          "type": "em"
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
+
+#[test]
+fn open_details() {
+    let html = "<details open><summary><i>Shown</i> title</summary><p>Visible</p></details>";
+    let expected = |attrs: serde_json::Value| {
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "expand",
+              "attrs": attrs,
+              "content": [
+                {
+                  "type": "paragraph",
+                  "content": [
+                    {
+                      "type": "text",
+                      "text": "Visible"
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        })
+    };
+    assert_output_json_eq(html, expected(json!({"title": "Shown title"})));
+    assert_output_json_eq_with_options(
+        html,
+        &ConverterOptions {
+            capture_expanded_state: true,
+            ..Default::default()
+        },
+        expected(json!({"title": "Shown title", "__expanded": true})),
+    );
+}
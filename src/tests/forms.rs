@@ -0,0 +1,110 @@
+#[allow(unused_imports)]
+use super::{assert_output_json_eq, assert_output_json_eq_with_options};
+
+#[allow(unused_imports)]
+use crate::{ConverterOptions, FormFieldPolicy};
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[cfg(test)]
+#[test]
+fn text_inputs_become_text() {
+    assert_output_json_eq(
+        r#"<p>Name: <input type="text" value="Ada"></p><p><input placeholder="Search..."></p><p>Secret <input type="password" value="x"><input type="checkbox" checked></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Name: "
+                        },
+                        {
+                            "type": "text",
+                            "text": "Ada"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Search..."
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Secret"
+                        }
+                    ]
+                }
+            ]
+        }),
+    )
+}
+
+#[test]
+fn textareas_and_buttons_become_paragraphs() {
+    assert_output_json_eq(
+        "<form><textarea>Some notes</textarea><button>Submit</button><select><option>One</option></select></form>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Some notes"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Submit"
+                        }
+                    ]
+                }
+            ]
+        }),
+    )
+}
+
+#[test]
+fn form_fields_can_be_dropped() {
+    assert_output_json_eq_with_options(
+        r#"<p>Name: <input value="Ada"></p><textarea>Some notes</textarea><button>Submit</button>"#,
+        &ConverterOptions {
+            form_field_policy: FormFieldPolicy::Drop,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Name:"
+                        }
+                    ]
+                }
+            ]
+        }),
+    )
+}
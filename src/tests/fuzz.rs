@@ -0,0 +1,181 @@
+#[allow(unused_imports)]
+use crate::{
+    convert_html_str_to_adf_str_single_pass, convert_html_str_to_adf_str_with_warnings, convert_html_str_to_storage_format,
+    try_convert_html_str_to_adf_str, ConverterOptions, HtmlToAdfError, ReversedListPolicy,
+};
+
+// Random HTML is built from these, so that it reaches the less travelled paths of the conversion.
+// The fuzz target (in fuzz/) feeds in arbitrary bytes instead.
+#[cfg(test)]
+static TAGS: [&str; 48] = [
+    "p", "b", "i", "a", "ul", "ol", "li", "table", "thead", "tfoot", "tr", "td", "th", "h1", "h7", "pre", "code",
+    "blockquote", "img", "iframe", "figure", "figcaption", "details", "summary", "br", "hr", "div", "span", "font",
+    "mark", "q", "del", "small", "input", "textarea", "select", "script", "template", "caption", "colgroup", "html",
+    "body", "head", "svg", "x-foo", "dl", "abbr", "sub",
+];
+
+#[cfg(test)]
+static ATTRIBUTES: [&str; 30] = [
+    r#"style="color: rgb(1%, 300, 2.5.5)""#,
+    r#"style=":;:""#,
+    r#"style="white-space: pre""#,
+    r#"style="color: rgba(0 0 0 / 0)""#,
+    r#"colspan="0""#,
+    r#"colspan="99999999999999999999""#,
+    r#"rowspan="-3""#,
+    r#"start="18446744073709551616""#,
+    "reversed",
+    "open",
+    r#"href="""#,
+    r#"href="//x""#,
+    r#"src="""#,
+    r#"srcset="a.png 2x, b.png 100w, , c.png 1.5x""#,
+    r#"width="1e99""#,
+    r#"height="-0.5""#,
+    r#"data-width="12.7px""#,
+    r#"data-color="nope""#,
+    r#"data-emoji-short-name="""#,
+    r#"class="language-""#,
+    r#"lang="c++""#,
+    r#"role="heading""#,
+    r#"aria-level="99999999999""#,
+    r#"aria-hidden="true""#,
+    "contenteditable",
+    r#"dir="rtl""#,
+    r#"datetime="x""#,
+    r#"headers="a b""#,
+    r#"data-annotation-id="""#,
+    r#"placeholder="é""#,
+];
+
+#[cfg(test)]
+static TEXTS: [&str; 20] = [
+    "hello", " ", " \n ", "😀", "a\u{301}", "&amp;", "&#0;", "&#xD800;", "<", ">", "]]>", "`code`", "\r\n",
+    "<!-- c -->", "<![CDATA[x]]>", "<!doctype html>", "</", "&", "\u{fffd}", "\t",
+];
+
+/**
+ * A deterministic xorshift generator, so any failure can be reproduced from its seed.
+ */
+#[cfg(test)]
+struct RandomHtml(u64);
+
+#[cfg(test)]
+impl RandomHtml {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[(self.next() % items.len() as u64) as usize]
+    }
+
+    /**
+     * Tag soup: elements (some left unclosed), stray closing tags and text, nested a few levels deep.
+     */
+    fn push_content(&mut self, html: &mut String, depth: u32) {
+        for _ in 0..self.next() % 5 {
+            match self.next() % 10 {
+                0..=3 => html.push_str(self.pick(&TEXTS)),
+                4 => html.push_str(&format!("</{}>", self.pick(&TAGS))),
+                _ => {
+                    let tag = self.pick(&TAGS);
+                    html.push_str(&format!("<{tag}"));
+                    for _ in 0..self.next() % 3 {
+                        html.push_str(&format!(" {}", self.pick(&ATTRIBUTES)));
+                    }
+                    html.push('>');
+                    if depth < 6 {
+                        self.push_content(html, depth + 1);
+                    }
+                    if !self.next().is_multiple_of(4) {
+                        html.push_str(&format!("</{tag}>"));
+                    }
+                }
+            }
+        }
+    }
+
+    fn html(&mut self) -> String {
+        let mut html = String::new();
+        if self.next().is_multiple_of(10) {
+            let bytes: Vec<u8> = (0..self.next() % 64).map(|_| self.next() as u8).collect();
+            html = String::from_utf8_lossy(&bytes).to_string();
+        } else {
+            self.push_content(&mut html, 0);
+        }
+        html
+    }
+}
+
+/**
+ * Run every entry point over the HTML. Any panic fails the test, the only error allowed is a too large document.
+ */
+#[cfg(test)]
+#[allow(dead_code)]
+fn convert_everywhere(html: &str) {
+    let options = [
+        ConverterOptions::default(),
+        ConverterOptions {
+            split_paragraphs_at_double_breaks: true,
+            trim_document: true,
+            omit_trailing_empty_cells: true,
+            preserve_line_breaks_in_paragraphs: true,
+            markdown_inline_code: true,
+            max_text_node_len: Some(3),
+            capture_comments: true,
+            max_output_nodes: Some(20),
+            reversed_list_policy: ReversedListPolicy::Reverse,
+            ..Default::default()
+        },
+    ];
+    for options in options.iter() {
+        match try_convert_html_str_to_adf_str(html.to_string(), options) {
+            Ok(_) | Err(HtmlToAdfError::TooLarge { .. }) => {}
+            Err(error) => panic!("{error} for {html:?}"),
+        }
+        convert_html_str_to_adf_str_single_pass(html.to_string(), options);
+        convert_html_str_to_adf_str_with_warnings(html.to_string(), options);
+        convert_html_str_to_storage_format(html.to_string(), options);
+    }
+}
+
+#[test]
+fn random_html_converts() {
+    let mut random = RandomHtml(0x2545_f491_4f6c_dd1d);
+    for _ in 0..300 {
+        convert_everywhere(&random.html());
+    }
+}
+
+#[test]
+fn regressions_convert() {
+    let cases = [
+        "<td style=\"color: rgb(1.2.3, 0, 0)\">x",
+        "<ol start=\"18446744073709551616\"><li reversed>x",
+        "<img srcset=\", ,\"><img srcset=\"a.png 0x\">",
+        "<p>&#xD800;<q><q></p>😀</q>",
+        "<table><tr><td colspan=\"99999999999999999999\" rowspan=\"-1\"><br>",
+        "<h1 aria-level=\"99999999999\" role=\"heading\"><pre>",
+        "\u{0}<\u{fffd}</<!--",
+    ];
+    cases.iter().for_each(|html| convert_everywhere(html));
+}
+
+#[test]
+fn deeply_nested_html_converts() {
+    // Cells used to be checked for text recursively, which overflowed the stack for deeply nested tables
+    let html = "<table><tr><td>".repeat(200) + "x";
+    std::thread::Builder::new()
+        .stack_size(256 * 1024)
+        .spawn(move || {
+            try_convert_html_str_to_adf_str(html.clone(), &ConverterOptions::default()).unwrap();
+            convert_html_str_to_adf_str_single_pass(html, &ConverterOptions::default());
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
@@ -1,6 +1,10 @@
 #[cfg(test)]
 #[allow(unused_imports)]
 use super::assert_output_json_eq;
+#[allow(unused_imports)]
+use super::assert_output_json_eq_with_options;
+#[allow(unused_imports)]
+use crate::ConverterOptions;
 
 #[allow(unused_imports)]
 use serde_json::json;
@@ -179,3 +183,169 @@ fn h7() {
         }),
     );
 }
+
+#[test]
+fn clamped_heading_level() {
+    assert_output_json_eq_with_options(
+        "<h5>H5</h5>",
+        &ConverterOptions {
+            max_heading_level: 3,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "heading",
+                    "attrs": {
+                        "level": 3
+                    },
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "H5"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn aria_heading() {
+    assert_output_json_eq(
+        r#"<div role="heading" aria-level="2">Aria</div><div role="heading">Default</div>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "heading",
+                    "attrs": {
+                        "level": 2
+                    },
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Aria"
+                        }
+                    ]
+                },
+                {
+                    "type": "heading",
+                    "attrs": {
+                        "level": 1
+                    },
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Default"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn clamped_aria_heading_level() {
+    assert_output_json_eq(
+        r#"<div role="heading" aria-level="9">Deep</div>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "heading",
+                    "attrs": {
+                        "level": 6
+                    },
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Deep"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn heading_ids() {
+    let html = r#"<h2 id="intro">Introduction</h2>"#;
+    let heading = |attrs: serde_json::Value| {
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "heading",
+                    "attrs": attrs,
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Introduction"
+                        }
+                    ]
+                }
+            ]
+        })
+    };
+    assert_output_json_eq(html, heading(json!({ "level": 2 })));
+    assert_output_json_eq_with_options(
+        html,
+        &ConverterOptions {
+            capture_heading_ids: true,
+            ..Default::default()
+        },
+        heading(json!({ "level": 2, "localId": "intro" })),
+    );
+}
+
+#[test]
+fn spaces_between_inline_elements() {
+    assert_output_json_eq(
+        "<h2><b>Bold</b> <i>italic</i> </h2>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "heading",
+                    "attrs": {
+                        "level": 2
+                    },
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Bold",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "italic",
+                            "marks": [
+                                {
+                                    "type": "em"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
@@ -0,0 +1,91 @@
+#[allow(unused_imports)]
+use super::assert_output_json_eq_with_options;
+
+#[allow(unused_imports)]
+use crate::ConverterOptions;
+
+#[allow(unused_imports)]
+use serde_json::{json, Value};
+
+#[cfg(test)]
+#[test]
+fn attribute_hook_rewrites_image_urls() {
+    let options = ConverterOptions::default().with_attribute_hook("img", |element| {
+        match element.value().attr("src") {
+            Some(src) => vec![(
+                "url".to_string(),
+                Value::String(src.replace("http://old.example.com", "https://cdn.example.com")),
+            )],
+            None => vec![],
+        }
+    });
+    assert_output_json_eq_with_options(
+        "<img src='http://old.example.com/a.jpg'/>",
+        &options,
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "mediaSingle",
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "https://cdn.example.com/a.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
+
+#[test]
+fn attribute_hook_adds_attributes() {
+    let options = ConverterOptions::default().with_attribute_hook("td", |element| {
+        match element.value().attr("bgcolor") {
+            Some(color) => vec![("background".to_string(), Value::String(color.to_string()))],
+            None => vec![],
+        }
+    });
+    assert_output_json_eq_with_options(
+        "<table><tr><td bgcolor='#ff0000'>A</td></tr></table>",
+        &options,
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "table",
+              "content": [
+                {
+                  "type": "tableRow",
+                  "content": [
+                    {
+                      "type": "tableCell",
+                      "attrs": {
+                        "background": "#ff0000"
+                      },
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "A"
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
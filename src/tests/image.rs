@@ -54,3 +54,469 @@ fn relative_image() {
         }),
     );
 }
+
+#[test]
+fn stable_attribute_order() {
+    let html = "<img data-media-id='abc' data-collection='files' alt='Alt' data-width='120' data-height='80' data-width-type='pixel'/>
+        <table><tr><td colspan='2' data-colwidth='60,60'>Cell</td></tr></table>"
+        .to_string();
+    let converted = crate::convert_html_str_to_adf_str(html.clone());
+    (0..10).for_each(|_| assert_eq!(converted, crate::convert_html_str_to_adf_str(html.clone())));
+    assert_eq!(
+        converted,
+        r#"{"version":1,"type":"doc","content":[{"type":"mediaSingle","content":[{"type":"media","attrs":{"id":"abc","type":"file","collection":"files","alt":"Alt","width":120,"height":80,"widthType":"pixel"}}]},{"type":"table","content":[{"type":"tableRow","content":[{"type":"tableCell","attrs":{"colspan":2,"colwidth":[60,60]},"content":[{"type":"paragraph","content":[{"type":"text","text":"Cell"}]}]}]}]}]}"#
+    );
+}
+
+#[test]
+fn image_layout() {
+    assert_output_json_eq(
+        "<img src='a.jpg' data-layout='wrap-left'/><img src='b.jpg' data-layout='sideways'/>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "mediaSingle",
+              "attrs": {
+                "layout": "wrap-left"
+              },
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "a.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            },
+            {
+              "type": "mediaSingle",
+              "attrs": {
+                "layout": "center"
+              },
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "b.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
+
+#[test]
+fn media_single_width() {
+    assert_output_json_eq(
+        "<img src='a.jpg' data-media-single-width='50' data-media-single-width-type='percentage'/>
+        <img src='b.jpg' data-media-single-width='30' data-media-single-width-type='inches'/>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "mediaSingle",
+              "attrs": {
+                "width": 50,
+                "widthType": "percentage"
+              },
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "a.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            },
+            {
+              "type": "mediaSingle",
+              "attrs": {
+                "width": 30
+              },
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "b.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
+
+#[test]
+fn media_single_width_range() {
+    assert_output_json_eq(
+        "<img src='a.jpg' data-media-single-width='-10'/>
+        <img src='b.jpg' data-media-single-width='150%' data-media-single-width-type='percentage'/>
+        <img src='c.jpg' data-media-single-width='800' data-media-single-width-type='pixel'/>
+        <img src='d.jpg' data-media-single-width='-800' data-media-single-width-type='pixel'/>
+        <img src='e.jpg' data-media-single-width='wide'/>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "mediaSingle",
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "a.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            },
+            {
+              "type": "mediaSingle",
+              "attrs": {
+                "width": 100,
+                "widthType": "percentage"
+              },
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "b.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            },
+            {
+              "type": "mediaSingle",
+              "attrs": {
+                "width": 800,
+                "widthType": "pixel"
+              },
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "c.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            },
+            {
+              "type": "mediaSingle",
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "d.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            },
+            {
+              "type": "mediaSingle",
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "e.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
+
+#[test]
+fn dimensions_are_integers() {
+    let html = "<img data-media-id='abc' data-width='120.0' data-height='79.6' data-media-single-width='50.0'/>
+        <table><tr><td style='width: 100.0px'>Cell</td></tr></table>
+        <h2>Heading</h2>"
+        .to_string();
+    let converted = crate::convert_html_str_to_adf_str(html.clone());
+    assert_eq!(
+        converted,
+        r#"{"version":1,"type":"doc","content":[{"type":"mediaSingle","attrs":{"width":50},"content":[{"type":"media","attrs":{"id":"abc","type":"file","width":120,"height":80}}]},{"type":"table","content":[{"type":"tableRow","content":[{"type":"tableCell","attrs":{"colwidth":[100]},"content":[{"type":"paragraph","content":[{"type":"text","text":"Cell"}]}]}]}]},{"type":"heading","attrs":{"level":2},"content":[{"type":"text","text":"Heading"}]}]}"#
+    );
+}
+
+#[test]
+fn image_source_from_srcset() {
+    assert_output_json_eq(
+        "<img srcset='small.jpg 480w, large.jpg 1080w, medium.jpg 800w' sizes='50vw' loading='lazy' decoding='async' fetchpriority='low'/>
+        <img srcset='a.jpg, b.jpg 2x' loading='lazy'/>
+        <img src='c.jpg' srcset='d.jpg 2x'/>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "mediaSingle",
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "large.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            },
+            {
+              "type": "mediaSingle",
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "b.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            },
+            {
+              "type": "mediaSingle",
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "c.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
+
+#[test]
+fn figure_with_caption() {
+    assert_output_json_eq(
+        "<p>Before</p><figure data-x='1'><img src='a.jpg' data-layout='wide'><figcaption>A <b>bold</b>\n caption</figcaption></figure><figure><img src='b.jpg'></figure>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "paragraph",
+              "content": [
+                {
+                  "type": "text",
+                  "text": "Before"
+                }
+              ]
+            },
+            {
+              "type": "mediaSingle",
+              "attrs": {
+                "layout": "wide"
+              },
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "a.jpg",
+                    "type": "external"
+                  }
+                },
+                {
+                  "type": "caption",
+                  "content": [
+                    {
+                      "type": "text",
+                      "text": "A "
+                    },
+                    {
+                      "type": "text",
+                      "text": "bold",
+                      "marks": [
+                        {
+                          "type": "strong"
+                        }
+                      ]
+                    },
+                    {
+                      "type": "text",
+                      "text": " caption"
+                    }
+                  ]
+                }
+              ]
+            },
+            {
+              "type": "mediaSingle",
+              "content": [
+                {
+                  "type": "media",
+                  "attrs": {
+                    "url": "b.jpg",
+                    "type": "external"
+                  }
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
+
+#[test]
+fn atlassian_emoji() {
+    assert_output_json_eq(
+        "<p>Done <img class=\"emoji\" src=\"https://example.com/1f389.png\" data-emoji-short-name=\":tada:\" \
+         data-emoji-id=\"1f389\" data-emoji-text=\"🎉\" alt=\":tada:\"> today</p>\
+         <table><tr><td><img data-emoji-short-name=\":smile:\"></td></tr></table>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Done "
+                        },
+                        {
+                            "type": "emoji",
+                            "attrs": {
+                                "shortName": ":tada:",
+                                "id": "1f389",
+                                "text": "🎉"
+                            }
+                        },
+                        {
+                            "type": "text",
+                            "text": " today"
+                        }
+                    ]
+                },
+                {
+                    "type": "table",
+                    "content": [
+                        {
+                            "type": "tableRow",
+                            "content": [
+                                {
+                                    "type": "tableCell",
+                                    "content": [
+                                        {
+                                            "type": "paragraph",
+                                            "content": [
+                                                {
+                                                    "type": "emoji",
+                                                    "attrs": {
+                                                        "shortName": ":smile:"
+                                                    }
+                                                }
+                                            ]
+                                        }
+                                    ]
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn figure_wrapped_picture() {
+    assert_output_json_eq(
+        r#"<figure><picture><source media="(min-width: 800px)" srcset="wide.png"><img src="narrow.png" alt="A cat asleep"></picture><figcaption>Our cat</figcaption></figure>
+        <picture><source srcset="small.webp 1x, large.webp 2x" type="image/webp"><img alt="Fallback"></picture>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "mediaSingle",
+                    "content": [
+                        {
+                            "type": "media",
+                            "attrs": {
+                                "url": "narrow.png",
+                                "type": "external",
+                                "alt": "A cat asleep"
+                            }
+                        },
+                        {
+                            "type": "caption",
+                            "content": [
+                                {
+                                    "type": "text",
+                                    "text": "Our cat"
+                                }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "type": "mediaSingle",
+                    "content": [
+                        {
+                            "type": "media",
+                            "attrs": {
+                                "url": "large.webp",
+                                "type": "external",
+                                "alt": "Fallback"
+                            }
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn linked_image() {
+    assert_output_json_eq(
+        r#"<p><a href="https://example.com/gallery"><img src="https://example.com/a.png" alt="A"></a></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "mediaSingle",
+                    "content": [
+                        {
+                            "type": "media",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://example.com/gallery"
+                                    }
+                                }
+                            ],
+                            "attrs": {
+                                "url": "https://example.com/a.png",
+                                "type": "external",
+                                "alt": "A"
+                            }
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
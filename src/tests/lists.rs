@@ -1,5 +1,11 @@
 #[allow(unused_imports)]
-use super::assert_output_json_eq;
+use super::{assert_output_json_eq, assert_output_json_eq_with_options};
+
+#[allow(unused_imports)]
+use crate::{
+    convert_html_str_to_adf_str_with_warnings, ConversionWarning, ConverterOptions, ListItemValuePolicy, ReversedListPolicy,
+    SourcePosition,
+};
 
 #[allow(unused_imports)]
 use serde_json::json;
@@ -167,7 +173,7 @@ fn nested_1() {
                       "content": [
                         {
                           "type": "text",
-                          "text": " Nested List "
+                          "text": "Nested List"
                         }
                       ]
                     },
@@ -226,3 +232,217 @@ fn nested_1() {
         }),
     );
 }
+
+#[test]
+fn leading_inline_content_is_wrapped() {
+    assert_output_json_eq(
+        "<ul><li><b>bold</b> plain<ul><li>nested</li></ul></li></ul>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "bulletList",
+              "content": [
+                {
+                  "type": "listItem",
+                  "content": [
+                    {
+                      "type": "paragraph",
+                      "content": [
+                        {
+                          "type": "text",
+                          "text": "bold",
+                          "marks": [
+                            {
+                              "type": "strong"
+                            }
+                          ]
+                        },
+                        {
+                          "type": "text",
+                          "text": " plain"
+                        }
+                      ]
+                    },
+                    {
+                      "type": "bulletList",
+                      "content": [
+                        {
+                          "type": "listItem",
+                          "content": [
+                            {
+                              "type": "paragraph",
+                              "content": [
+                                {
+                                  "type": "text",
+                                  "text": "nested"
+                                }
+                              ]
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
+
+#[allow(dead_code)]
+fn list_item(text: &str) -> serde_json::Value {
+    json!({
+        "type": "listItem",
+        "content": [
+            {
+                "type": "paragraph",
+                "content": [
+                    {
+                        "type": "text",
+                        "text": text
+                    }
+                ]
+            }
+        ]
+    })
+}
+
+#[test]
+fn ordered_list_start() {
+    assert_output_json_eq(
+        r#"<ol start="4"><li>Four</li><li>Five</li></ol>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "orderedList",
+                    "attrs": {
+                        "order": 4
+                    },
+                    "content": [list_item("Four"), list_item("Five")]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn reversed_list_keeps_source_order_with_a_warning() {
+    let html = "<ol reversed><li>Three</li><li>Two</li><li>One</li></ol>";
+    assert_output_json_eq(
+        html,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "orderedList",
+                    "content": [list_item("Three"), list_item("Two"), list_item("One")]
+                }
+            ]
+        }),
+    );
+    assert_eq!(
+        convert_html_str_to_adf_str_with_warnings(html.to_string(), &ConverterOptions::default()).1,
+        vec![ConversionWarning {
+            element: "ol".to_string(),
+            message: "reversed numbering is not represented, items keep their source order".to_string(),
+            position: Some(SourcePosition {
+                offset: 0,
+                line: 1,
+                column: 1,
+            }),
+        }]
+    );
+}
+
+#[test]
+fn reversed_list_can_be_reversed() {
+    assert_output_json_eq_with_options(
+        r#"<ol reversed><li>Three</li><li>Two</li><li>One</li></ol><ol reversed start="10"><li>Ten</li><li>Nine</li></ol>"#,
+        &ConverterOptions {
+            reversed_list_policy: ReversedListPolicy::Reverse,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "orderedList",
+                    "attrs": {
+                        "order": 1
+                    },
+                    "content": [list_item("One"), list_item("Two"), list_item("Three")]
+                },
+                {
+                    "type": "orderedList",
+                    "attrs": {
+                        "order": 9
+                    },
+                    "content": [list_item("Nine"), list_item("Ten")]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn first_item_value_starts_the_list() {
+    assert_output_json_eq(
+        r#"<ol><li value="5">Five</li><li>Six</li><li value="9">Nine</li></ol>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "orderedList",
+                    "attrs": {
+                        "order": 5
+                    },
+                    "content": [list_item("Five"), list_item("Six"), list_item("Nine")]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn item_values_can_be_reported() {
+    let html = r#"<ol><li value="5">Five</li><li>Six</li></ol>"#;
+    let options = ConverterOptions {
+        list_item_value_policy: ListItemValuePolicy::Warn,
+        ..Default::default()
+    };
+    assert_output_json_eq_with_options(
+        html,
+        &options,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "orderedList",
+                    "content": [list_item("Five"), list_item("Six")]
+                }
+            ]
+        }),
+    );
+    assert_eq!(
+        convert_html_str_to_adf_str_with_warnings(html.to_string(), &options).1,
+        vec![ConversionWarning {
+            element: "li".to_string(),
+            message: "explicit item numbering is not represented".to_string(),
+            position: Some(SourcePosition {
+                offset: 4,
+                line: 1,
+                column: 5,
+            }),
+        }]
+    );
+}
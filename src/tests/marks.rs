@@ -1,5 +1,11 @@
 #[allow(unused_imports)]
-use super::assert_output_json_eq;
+use super::{assert_output_json_eq, assert_output_json_eq_with_options};
+
+#[allow(unused_imports)]
+use crate::{marks_for_element, parse_html, AdfMarkAttributes, ConverterOptions, MarkOrder};
+
+#[allow(unused_imports)]
+use scraper::Selector;
 
 #[allow(unused_imports)]
 use serde_json::json;
@@ -37,3 +43,993 @@ fn top_level() {
         }),
     );
 }
+
+#[test]
+fn subsup_inside_pre() {
+    assert_output_json_eq(
+        "<pre>x <sub> 2 </sub>  + y<sup>n</sup> </pre>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "x "
+                        },
+                        {
+                            "type": "text",
+                            "text": " 2 ",
+                            "marks": [
+                                {
+                                    "type": "subsup",
+                                    "attrs": {
+                                        "type": "sub"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": "  + y"
+                        },
+                        {
+                            "type": "text",
+                            "text": "n",
+                            "marks": [
+                                {
+                                    "type": "subsup",
+                                    "attrs": {
+                                        "type": "sup"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn superscript_exponents_and_ordinals() {
+    // The mark covers the whole content, digits or not, next to the text it follows
+    assert_output_json_eq(
+        "<p>x<sup>2</sup> on the 4<sup>th</sup>, 10<sup>-3</sup>, H<sub>2</sub>O</p>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "x"
+                        },
+                        {
+                            "type": "text",
+                            "text": "2",
+                            "marks": [
+                                {
+                                    "type": "subsup",
+                                    "attrs": {
+                                        "type": "sup"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " on the 4"
+                        },
+                        {
+                            "type": "text",
+                            "text": "th",
+                            "marks": [
+                                {
+                                    "type": "subsup",
+                                    "attrs": {
+                                        "type": "sup"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": ", 10"
+                        },
+                        {
+                            "type": "text",
+                            "text": "-3",
+                            "marks": [
+                                {
+                                    "type": "subsup",
+                                    "attrs": {
+                                        "type": "sup"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": ", H"
+                        },
+                        {
+                            "type": "text",
+                            "text": "2",
+                            "marks": [
+                                {
+                                    "type": "subsup",
+                                    "attrs": {
+                                        "type": "sub"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": "O"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn annotated_span() {
+    assert_output_json_eq(
+        r#"<p>See <span data-annotation-id="abc-123"><strong>this</strong></span> and <span data-annotation-id="">that</span></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "See "
+                        },
+                        {
+                            "type": "text",
+                            "text": "this",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                },
+                                {
+                                    "type": "annotation",
+                                    "attrs": {
+                                        "id": "abc-123",
+                                        "annotationType": "inlineComment"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " and "
+                        },
+                        {
+                            "type": "text",
+                            "text": "that"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn small_and_big_keep_their_text() {
+    assert_output_json_eq(
+        "<p><small>Fine <b>print</b></small> and <big>large</big></p>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Fine "
+                        },
+                        {
+                            "type": "text",
+                            "text": "print",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " and "
+                        },
+                        {
+                            "type": "text",
+                            "text": "large"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn muted_small_text() {
+    assert_output_json_eq_with_options(
+        "<p><small>Fine <b>print</b></small></p>",
+        &ConverterOptions {
+            muted_small_text: true,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Fine ",
+                            "marks": [
+                                {
+                                    "type": "textColor",
+                                    "attrs": {
+                                        "color": "#97a0af"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": "print",
+                            "marks": [
+                                {
+                                    "type": "textColor",
+                                    "attrs": {
+                                        "color": "#97a0af"
+                                    }
+                                },
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn strike_and_link() {
+    assert_output_json_eq(
+        r#"<p><del><a href="x">y</a></del> <a href="x"><s>z</s></a></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "y",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "x"
+                                    }
+                                },
+                                {
+                                    "type": "strike"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "z",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "x"
+                                    }
+                                },
+                                {
+                                    "type": "strike"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn canonical_mark_order() {
+    let html = r#"<p><u><b><span style="color: #ff0000"><a href="https://example.com">Text</a></span></b></u></p>"#;
+    assert_output_json_eq(
+        html,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Text",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://example.com"
+                                    }
+                                },
+                                {
+                                    "type": "textColor",
+                                    "attrs": {
+                                        "color": "#ff0000"
+                                    }
+                                },
+                                {
+                                    "type": "strong"
+                                },
+                                {
+                                    "type": "underline"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+    assert_output_json_eq_with_options(
+        html,
+        &ConverterOptions {
+            mark_order: MarkOrder::Source,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Text",
+                            "marks": [
+                                {
+                                    "type": "underline"
+                                },
+                                {
+                                    "type": "strong"
+                                },
+                                {
+                                    "type": "textColor",
+                                    "attrs": {
+                                        "color": "#ff0000"
+                                    }
+                                },
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://example.com"
+                                    }
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn misnested_marks() {
+    assert_output_json_eq(
+        "<p><b>bold <i>both</b> italic</i></p>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "bold ",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": "both",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                },
+                                {
+                                    "type": "em"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " italic",
+                            "marks": [
+                                {
+                                    "type": "em"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn misnested_link() {
+    assert_output_json_eq(
+        r#"<p><a href="https://example.com">one <b>two</a> three</b></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "one ",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://example.com"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": "two",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://example.com"
+                                    }
+                                },
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " three",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn cited_quote() {
+    assert_output_json_eq(
+        r#"<p>As <q cite="https://example.com/speech">we <b>shall</b> fight</q> and <q>so <q>on</q></q></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "As "
+                        },
+                        {
+                            "type": "text",
+                            "text": "“we ",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://example.com/speech"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": "shall",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://example.com/speech"
+                                    }
+                                },
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " fight”",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://example.com/speech"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " and "
+                        },
+                        {
+                            "type": "text",
+                            "text": "“so "
+                        },
+                        {
+                            "type": "text",
+                            "text": "‘on’”"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn definitions_are_italic() {
+    assert_output_json_eq(
+        r#"<p>A <dfn>term</dfn>, <a href="https://example.com"><dfn style="color: #ff0000">linked</dfn></a> and <abbr title="abbreviation">abbr</abbr></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "A "
+                        },
+                        {
+                            "type": "text",
+                            "text": "term",
+                            "marks": [
+                                {
+                                    "type": "em"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": ", "
+                        },
+                        {
+                            "type": "text",
+                            "text": "linked",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://example.com"
+                                    }
+                                },
+                                {
+                                    "type": "textColor",
+                                    "attrs": {
+                                        "color": "#ff0000"
+                                    }
+                                },
+                                {
+                                    "type": "em"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " and "
+                        },
+                        {
+                            "type": "text",
+                            "text": "abbr"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn italic_abbreviations() {
+    assert_output_json_eq_with_options(
+        r#"<p><abbr title="HyperText Markup Language">HTML</abbr></p>"#,
+        &ConverterOptions {
+            italic_abbreviations: true,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "HTML",
+                            "marks": [
+                                {
+                                    "type": "em"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn highlighted_annotation() {
+    assert_output_json_eq(
+        r##"<p><mark data-color="#ffeb3b" data-annotation-id="c1">both</mark> <mark data-color="rgb(255, 0, 0)">color</mark> <mark data-annotation-id="c2" data-color="none">comment</mark> <mark>neither</mark></p>"##,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "both",
+                            "marks": [
+                                {
+                                    "type": "backgroundColor",
+                                    "attrs": {
+                                        "color": "#ffeb3b"
+                                    }
+                                },
+                                {
+                                    "type": "annotation",
+                                    "attrs": {
+                                        "id": "c1",
+                                        "annotationType": "inlineComment"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "color",
+                            "marks": [
+                                {
+                                    "type": "backgroundColor",
+                                    "attrs": {
+                                        "color": "#ff0000"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "comment",
+                            "marks": [
+                                {
+                                    "type": "annotation",
+                                    "attrs": {
+                                        "id": "c2",
+                                        "annotationType": "inlineComment"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "neither",
+                            "marks": [
+                                {
+                                    "type": "backgroundColor",
+                                    "attrs": {
+                                        "color": "#fef445"
+                                    }
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn marks_of_elements() {
+    let fragment = parse_html(r#"<p>A <a href=x>link</a>, <b>bold</b> and <div>block</div> <custom>unmapped</custom></p>"#.to_string());
+    let marks = |name: &str| {
+        let element = fragment.select(&Selector::parse(name).unwrap()).next().unwrap();
+        marks_for_element(element)
+            .into_iter()
+            .map(|mark| match mark.attributes {
+                AdfMarkAttributes::Map(attributes) => (mark.typename, attributes),
+                AdfMarkAttributes::List(_) | AdfMarkAttributes::Generator(_) => panic!("attributes are not read"),
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(marks("a"), vec![("link".to_string(), vec![("href".to_string(), json!("x"))])]);
+    assert_eq!(marks("b"), vec![("strong".to_string(), vec![])]);
+    assert_eq!(marks("p"), vec![]);
+    assert_eq!(marks("div"), vec![]);
+    assert_eq!(marks("custom"), vec![]);
+}
+
+#[test]
+fn code_styled_links() {
+    let html = "<p><a href=\"https://example.com/api\"><code>api</code></a> and <code><a href=\"https://example.com/b\"><b>b</b></a></code></p>";
+    let expected = |first: &str, second: &str| {
+        let mark = |mark_type: &str, href: &str| match mark_type {
+            "link" => json!({"type": "link", "attrs": {"href": href}}),
+            _ => json!({"type": "code"}),
+        };
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "api",
+                            "marks": [mark("link", "https://example.com/api"), mark("code", "")]
+                        },
+                        {
+                            "type": "text",
+                            "text": " and "
+                        },
+                        {
+                            "type": "text",
+                            "text": "b",
+                            "marks": [mark(first, "https://example.com/b"), mark(second, "https://example.com/b")]
+                        }
+                    ]
+                }
+            ]
+        })
+    };
+    // Code only combines with links (and annotations), so the strong mark is dropped
+    assert_output_json_eq(html, expected("link", "code"));
+    assert_output_json_eq_with_options(
+        html,
+        &ConverterOptions {
+            mark_order: MarkOrder::Source,
+            ..Default::default()
+        },
+        expected("code", "link"),
+    );
+}
+
+#[test]
+fn monospace_spans_are_code() {
+    let html = r#"<p><span style="font-family: 'Courier New', monospace"><b>let</b> x</span> = <span style="font-family:Arial">1</span></p>"#;
+    assert_output_json_eq_with_options(
+        html,
+        &ConverterOptions {
+            monospace_code: true,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "let",
+                            "marks": [
+                                {
+                                    "type": "code"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " x",
+                            "marks": [
+                                {
+                                    "type": "code"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " = "
+                        },
+                        {
+                            "type": "text",
+                            "text": "1"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+    assert_output_json_eq(
+        html,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "let",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " x"
+                        },
+                        {
+                            "type": "text",
+                            "text": " = "
+                        },
+                        {
+                            "type": "text",
+                            "text": "1"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn bare_highlight_is_yellow() {
+    assert_output_json_eq(
+        "<p><mark>text</mark></p>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "text",
+                            "marks": [
+                                {
+                                    "type": "backgroundColor",
+                                    "attrs": {
+                                        "color": "#fef445"
+                                    }
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn links_without_href_are_dropped() {
+    assert_output_json_eq(
+        r#"<p><a>none</a><a href=" "><b>blank</b></a></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "none"
+                        },
+                        {
+                            "type": "text",
+                            "text": "blank",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn empty_marks_are_omitted() {
+    let converted = crate::convert_html_str_to_adf_str(r#"<p><a name="top">Top</a></p>"#.to_string());
+    assert!(!converted.contains("marks"), "{converted}");
+    let adf = crate::assemble(
+        crate::extract_leaves(&parse_html(r#"<h1><a id="x">Title</a></h1>"#.to_string()), &ConverterOptions::default()),
+        &ConverterOptions::default(),
+    );
+    assert_eq!(adf["content"][0]["content"][0], json!({"type": "text", "text": "Title"}));
+}
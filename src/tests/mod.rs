@@ -1,17 +1,53 @@
+mod assembly;
+mod blockquotes;
+mod code;
 mod colors;
 #[cfg(test)]
 mod combination;
+mod content_types;
+mod dates;
+mod documents;
+mod embeds;
 mod empty;
+mod expand;
+mod forms;
+mod fuzz;
 mod headings;
+mod hooks;
 mod image;
 mod lists;
 mod marks;
+mod sanitize;
 mod paragraphs;
+mod round_trip;
+mod storage_format;
 mod tables;
+mod transformers;
+mod urls;
+mod warnings;
+mod xhtml;
 use crate::convert_html_str_to_adf_str;
+use crate::convert_html_str_to_adf_str_single_pass;
+use crate::convert_html_str_to_adf_str_with_options;
+use crate::ConverterOptions;
 
+// Both assembly paths must always agree, so each expectation is checked against both.
 #[allow(dead_code)]
 fn assert_output_json_eq(html: &str, expected: serde_json::Value) {
     let converted = convert_html_str_to_adf_str(html.to_string());
     assert_eq!(expected.to_string(), converted);
+    let single_pass = convert_html_str_to_adf_str_single_pass(html.to_string(), &ConverterOptions::default());
+    assert_eq!(expected.to_string(), single_pass);
+}
+
+#[allow(dead_code)]
+fn assert_output_json_eq_with_options(
+    html: &str,
+    options: &ConverterOptions,
+    expected: serde_json::Value,
+) {
+    let converted = convert_html_str_to_adf_str_with_options(html.to_string(), options);
+    assert_eq!(expected.to_string(), converted);
+    let single_pass = convert_html_str_to_adf_str_single_pass(html.to_string(), options);
+    assert_eq!(expected.to_string(), single_pass);
 }
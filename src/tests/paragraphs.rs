@@ -1,5 +1,8 @@
 #[allow(unused_imports)]
-use super::assert_output_json_eq;
+use super::{assert_output_json_eq, assert_output_json_eq_with_options};
+
+#[allow(unused_imports)]
+use crate::ConverterOptions;
 
 #[allow(unused_imports)]
 use serde_json::json;
@@ -105,7 +108,7 @@ fn top_level() {
                 },
                 {
                     "type": "text",
-                    "text": "wewq "
+                    "text": "wewq"
                 }
                 ]
             },
@@ -115,7 +118,7 @@ fn top_level() {
                 [
                 {
                     "type": "text",
-                    "text": " "
+                    "text": "\u{a0}"
                 }
                 ]
             },
@@ -125,7 +128,7 @@ fn top_level() {
                 [
                 {
                     "type": "text",
-                    "text": " "
+                    "text": "\u{a0}"
                 }
                 ]
             },
@@ -135,7 +138,7 @@ fn top_level() {
                 [
                 {
                     "type": "text",
-                    "text": " "
+                    "text": "\u{a0}"
                 }
                 ]
             },
@@ -178,14 +181,14 @@ fn top_level() {
                 [
                 {
                     "type": "text",
-                    "text": " Naked "
+                    "text": "Naked "
                 },
                 {
                     "type": "hardBreak"
                 },
                 {
                     "type": "text",
-                    "text": " break "
+                    "text": " break"
                 }
                 ]
             },
@@ -254,3 +257,804 @@ fn top_level() {
         }),
     );
 }
+
+/**
+ *  Top level inline content is grouped into paragraphs
+ */
+#[test]
+fn top_level_inline_content() {
+    assert_output_json_eq(
+        "Hello <b>world</b><p>Paragraph</p>tail <i>end</i>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+            {
+                "type": "paragraph",
+                "content": [
+                {
+                    "type": "text",
+                    "text": "Hello "
+                },
+                {
+                    "type": "text",
+                    "text": "world",
+                    "marks": [
+                    {
+                        "type": "strong"
+                    }
+                    ]
+                }
+                ]
+            },
+            {
+                "type": "paragraph",
+                "content": [
+                {
+                    "type": "text",
+                    "text": "Paragraph"
+                }
+                ]
+            },
+            {
+                "type": "paragraph",
+                "content": [
+                {
+                    "type": "text",
+                    "text": "tail "
+                },
+                {
+                    "type": "text",
+                    "text": "end",
+                    "marks": [
+                    {
+                        "type": "em"
+                    }
+                    ]
+                }
+                ]
+            }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn hard_break_between_text() {
+    assert_output_json_eq(
+        "<p>a<br>b</p>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "paragraph",
+              "content": [
+                {
+                  "type": "text",
+                  "text": "a"
+                },
+                {
+                  "type": "hardBreak"
+                },
+                {
+                  "type": "text",
+                  "text": "b"
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
+
+#[test]
+fn hard_break_between_marked_text() {
+    assert_output_json_eq(
+        "<p><b>a</b><br>b<br><i>c</i></p>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "paragraph",
+              "content": [
+                {
+                  "type": "text",
+                  "text": "a",
+                  "marks": [
+                    {
+                      "type": "strong"
+                    }
+                  ]
+                },
+                {
+                  "type": "hardBreak"
+                },
+                {
+                  "type": "text",
+                  "text": "b"
+                },
+                {
+                  "type": "hardBreak"
+                },
+                {
+                  "type": "text",
+                  "text": "c",
+                  "marks": [
+                    {
+                      "type": "em"
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
+
+#[test]
+fn long_text_is_split() {
+    let text = "a".repeat(100_000);
+    let chunk = |len: usize| json!({ "type": "text", "text": "a".repeat(len), "marks": [{ "type": "strong" }] });
+    assert_output_json_eq_with_options(
+        &format!("<p><b>{text}</b></p>"),
+        &ConverterOptions {
+            max_text_node_len: Some(30_000),
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [chunk(30_000), chunk(30_000), chunk(30_000), chunk(10_000)]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn long_text_is_split_between_graphemes() {
+    // e followed by a combining acute accent is a single grapheme of two characters
+    assert_output_json_eq_with_options(
+        "<p>abe\u{301}cd</p>",
+        &ConverterOptions {
+            max_text_node_len: Some(3),
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "ab"
+                        },
+                        {
+                            "type": "text",
+                            "text": "e\u{301}c"
+                        },
+                        {
+                            "type": "text",
+                            "text": "d"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn line_breaks_in_paragraphs() {
+    let html = "<p>\n  First line\n  second <b>bold</b>\n  third\n</p>";
+    assert_output_json_eq(
+        html,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "First line second "
+                        },
+                        {
+                            "type": "text",
+                            "text": "bold",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " third"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+    assert_output_json_eq_with_options(
+        html,
+        &ConverterOptions {
+            preserve_line_breaks_in_paragraphs: true,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "First line"
+                        },
+                        {
+                            "type": "hardBreak"
+                        },
+                        {
+                            "type": "text",
+                            "text": "second "
+                        },
+                        {
+                            "type": "text",
+                            "text": "bold",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "hardBreak"
+                        },
+                        {
+                            "type": "text",
+                            "text": "third"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn adjacent_rules_collapse() {
+    let html = "<p>Above</p><hr><hr/><hr><p>Below</p>";
+    let document = |rules: usize| {
+        let mut content = vec![json!({ "type": "paragraph", "content": [{ "type": "text", "text": "Above" }] })];
+        content.extend(std::iter::repeat_n(json!({ "type": "rule" }), rules));
+        content.push(json!({ "type": "paragraph", "content": [{ "type": "text", "text": "Below" }] }));
+        json!({ "version": 1, "type": "doc", "content": content })
+    };
+    assert_output_json_eq(html, document(1));
+    assert_output_json_eq_with_options(
+        html,
+        &ConverterOptions {
+            keep_adjacent_rules: true,
+            ..Default::default()
+        },
+        document(3),
+    );
+}
+
+#[test]
+fn white_space_pre_style() {
+    assert_output_json_eq(
+        r#"<div style="white-space:pre">a   b</div><p style="white-space: pre-wrap">c  <b>d  e</b></p><p>f   g</p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "a   b"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "c  "
+                        },
+                        {
+                            "type": "text",
+                            "text": "d  e",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "f g"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn spaces_between_inline_elements() {
+    assert_output_json_eq(
+        "<p>Hello <b>world</b>!</p><p>One<i> two</i> <u>three </u>four</p>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Hello "
+                        },
+                        {
+                            "type": "text",
+                            "text": "world",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": "!"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "One"
+                        },
+                        {
+                            "type": "text",
+                            "text": " two",
+                            "marks": [
+                                {
+                                    "type": "em"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "three ",
+                            "marks": [
+                                {
+                                    "type": "underline"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": "four"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn whitespace_collapses_across_inline_elements() {
+    assert_output_json_eq(
+        "<p>A  <b>  B <i> C</i></b>\n <a href=\"https://example.com\"> </a> D</p><p>E <span> </span><br> F</p>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "A "
+                        },
+                        {
+                            "type": "text",
+                            "text": "B ",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": "C",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                },
+                                {
+                                    "type": "em"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "D"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "E "
+                        },
+                        {
+                            "type": "hardBreak"
+                        },
+                        {
+                            "type": "text",
+                            "text": " F"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn double_breaks_stay_within_the_paragraph() {
+    assert_output_json_eq(
+        "<p>a<br><br>b</p><ul><li>c<br><br>d</li></ul>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "a"
+                        },
+                        {
+                            "type": "hardBreak"
+                        },
+                        {
+                            "type": "hardBreak"
+                        },
+                        {
+                            "type": "text",
+                            "text": "b"
+                        }
+                    ]
+                },
+                {
+                    "type": "bulletList",
+                    "content": [
+                        {
+                            "type": "listItem",
+                            "content": [
+                                {
+                                    "type": "paragraph",
+                                    "content": [
+                                        {
+                                            "type": "text",
+                                            "text": "c"
+                                        },
+                                        {
+                                            "type": "hardBreak"
+                                        },
+                                        {
+                                            "type": "hardBreak"
+                                        },
+                                        {
+                                            "type": "text",
+                                            "text": "d"
+                                        }
+                                    ]
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn double_breaks_split_paragraphs() {
+    assert_output_json_eq_with_options(
+        "<p>a <br>\n<br> b<br>c<br><br><br>d</p><ul><li>e<br><br>f</li></ul>",
+        &ConverterOptions {
+            split_paragraphs_at_double_breaks: true,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "a"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "b"
+                        },
+                        {
+                            "type": "hardBreak"
+                        },
+                        {
+                            "type": "text",
+                            "text": "c"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "d"
+                        }
+                    ]
+                },
+                {
+                    "type": "bulletList",
+                    "content": [
+                        {
+                            "type": "listItem",
+                            "content": [
+                                {
+                                    "type": "paragraph",
+                                    "content": [
+                                        {
+                                            "type": "text",
+                                            "text": "e"
+                                        }
+                                    ]
+                                },
+                                {
+                                    "type": "paragraph",
+                                    "content": [
+                                        {
+                                            "type": "text",
+                                            "text": "f"
+                                        }
+                                    ]
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn nbsp_indentation() {
+    assert_output_json_eq_with_options(
+        "<p>&nbsp;&nbsp;&nbsp;&nbsp;One</p><p>&nbsp;&nbsp;&nbsp;&nbsp;&nbsp;&nbsp;&nbsp;&nbsp;&nbsp;Two</p><p>&nbsp;&nbsp;Three</p><ul><li>&nbsp;&nbsp;&nbsp;&nbsp;Four</li></ul>",
+        &ConverterOptions {
+            nbsp_indentation: true,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "marks": [
+                        {
+                            "type": "indentation",
+                            "attrs": {
+                                "level": 1
+                            }
+                        }
+                    ],
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "One"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "marks": [
+                        {
+                            "type": "indentation",
+                            "attrs": {
+                                "level": 2
+                            }
+                        }
+                    ],
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Two"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "\u{a0}\u{a0}Three"
+                        }
+                    ]
+                },
+                {
+                    "type": "bulletList",
+                    "content": [
+                        {
+                            "type": "listItem",
+                            "content": [
+                                {
+                                    "type": "paragraph",
+                                    "content": [
+                                        {
+                                            "type": "text",
+                                            "text": "\u{a0}\u{a0}\u{a0}\u{a0}Four"
+                                        }
+                                    ]
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn whitespace_at_block_edges() {
+    assert_output_json_eq(
+        r#"<p>  Leading and <b> bold </b> trailing  </p><h2> <i>Title </i> </h2><p style="white-space: pre">  kept  </p><p> &nbsp;x&nbsp; </p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Leading and "
+                        },
+                        {
+                            "type": "text",
+                            "text": "bold ",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": "trailing"
+                        }
+                    ]
+                },
+                {
+                    "type": "heading",
+                    "attrs": {
+                        "level": 2
+                    },
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Title",
+                            "marks": [
+                                {
+                                    "type": "em"
+                                }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "  kept  "
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "\u{a0}x\u{a0}"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn non_breaking_spaces_are_kept() {
+    assert_output_json_eq(
+        "<p>a&nbsp;&nbsp;&nbsp;b</p><p>&nbsp;&nbsp;Hi</p>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "a\u{a0}\u{a0}\u{a0}b"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "\u{a0}\u{a0}Hi"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
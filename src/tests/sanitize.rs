@@ -0,0 +1,273 @@
+#[allow(unused_imports)]
+use super::assert_output_json_eq_with_options;
+
+#[allow(unused_imports)]
+use crate::{ConverterOptions, UnknownElementPolicy};
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[cfg(test)]
+#[test]
+fn allowlist_unwraps_disallowed_tags() {
+    assert_output_json_eq_with_options(
+        r#"<p><strong>Bold</strong> <a href="https://example.com">link</a></p><h1>Title</h1><img src="a.png"/>"#,
+        &ConverterOptions {
+            allowed_tags: Some(vec!["p".to_string(), "strong".to_string()]),
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Bold",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "link"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Title"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn allowlist_drops_disallowed_tags() {
+    assert_output_json_eq_with_options(
+        r#"<p><strong>Bold</strong> <a href="https://example.com">link</a></p><h1>Title</h1>"#,
+        &ConverterOptions {
+            allowed_tags: Some(vec!["p".to_string(), "strong".to_string()]),
+            unknown_element_policy: UnknownElementPolicy::Drop,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Bold",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn allowlist_unwraps_code_blocks() {
+    assert_output_json_eq_with_options(
+        "<pre><span>let x = 1;</span></pre>",
+        &ConverterOptions {
+            allowed_tags: Some(vec!["p".to_string()]),
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "let x = 1;"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn aria_hidden_elements_are_excluded() {
+    assert_output_json_eq_with_options(
+        r#"<p><span class="icon" aria-hidden="true">★</span>Starred</p><table><tr><td><i aria-hidden="true">icon</i></td><td>B</td></tr></table>"#,
+        &ConverterOptions {
+            exclude_aria_hidden: true,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Starred"
+                        }
+                    ]
+                },
+                {
+                    "type": "table",
+                    "content": [
+                        {
+                            "type": "tableRow",
+                            "content": [
+                                {
+                                  "type": "tableCell",
+                                  "content": [
+                                    {
+                                      "type": "paragraph"
+                                    }
+                                  ]
+                                },
+                                {
+                                    "type": "tableCell",
+                                    "content": [
+                                        {
+                                            "type": "paragraph",
+                                            "content": [
+                                                {
+                                                    "type": "text",
+                                                    "text": "B"
+                                                }
+                                            ]
+                                        }
+                                    ]
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn template_and_noscript_are_excluded() {
+    assert_output_json_eq_with_options(
+        "<p>One</p><template><p>Template</p></template><noscript><p>Enable JavaScript</p></noscript><p>Two</p>",
+        &ConverterOptions::default(),
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "One"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Two"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn scripts_and_styles_are_excluded() {
+    let expected = json!({
+        "version": 1,
+        "type": "doc",
+        "content": [
+            {
+                "type": "paragraph",
+                "content": [
+                    {
+                        "type": "text",
+                        "text": "One"
+                    },
+                    {
+                        "type": "text",
+                        "text": "Two"
+                    }
+                ]
+            }
+        ]
+    });
+    let html = "<p>One<script>alert(1)</script><style>p { color: red }</style>Two</p>";
+    assert_output_json_eq_with_options(html, &ConverterOptions::default(), expected.clone());
+    // Unwrapping a disallowed script must not leak its text either
+    assert_output_json_eq_with_options(
+        html,
+        &ConverterOptions {
+            allowed_tags: Some(vec!["p".to_string()]),
+            ..Default::default()
+        },
+        expected.clone(),
+    );
+    assert_output_json_eq_with_options(
+        html,
+        &ConverterOptions {
+            allowed_tags: Some(vec!["p".to_string(), "script".to_string(), "style".to_string()]),
+            ..Default::default()
+        },
+        expected,
+    );
+}
+
+#[test]
+fn display_none_elements_are_excluded() {
+    assert_output_json_eq_with_options(
+        r#"<p style="DISPLAY: none !important">Hidden</p><p>Shown<span style="color: red; display:none">, not this</span></p><div style="display: none"><p>Nor this</p></div>"#,
+        &ConverterOptions {
+            exclude_display_none: true,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Shown"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
@@ -0,0 +1,68 @@
+#[allow(unused_imports)]
+use crate::{convert_html_str_to_storage_format, ConverterOptions};
+
+#[cfg(test)]
+#[allow(dead_code)]
+fn storage_format(html: &str) -> String {
+    convert_html_str_to_storage_format(html.to_string(), &ConverterOptions::default())
+}
+
+#[test]
+fn storage_format_text_and_marks() {
+    assert_eq!(
+        storage_format(r#"<h2>Title</h2><p>Some <b>bold</b>, <a href="https://example.com/?a=1&b=2">linked</a> &amp; <span style="color: #ff0000">red</span><br>text</p>"#),
+        concat!(
+            "<h2>Title</h2>",
+            "<p>Some <strong>bold</strong>, ",
+            r#"<a href="https://example.com/?a=1&amp;b=2">linked</a> &amp; "#,
+            r#"<span style="color: #ff0000">red</span><br />text</p>"#
+        )
+    );
+}
+
+#[test]
+fn storage_format_blocks() {
+    assert_eq!(
+        storage_format(r#"<ol start="3"><li>Three</li></ol><blockquote><p>Quote</p></blockquote><hr><table><tr><th>Head</th></tr><tr><td colspan="2">Cell</td></tr></table>"#),
+        concat!(
+            r#"<ol start="3"><li><p>Three</p></li></ol>"#,
+            "<blockquote><p>Quote</p></blockquote>",
+            "<hr />",
+            "<table><tbody><tr><th><p>Head</p></th></tr>",
+            r#"<tr><td colspan="2"><p>Cell</p></td></tr></tbody></table>"#
+        )
+    );
+}
+
+#[test]
+fn storage_format_macros() {
+    assert_eq!(
+        storage_format(r#"<pre><code class="language-rust">if a < b { c[d[0]]> }</code></pre><img src="https://example.com/a.png"><iframe src="https://example.com/video"></iframe>"#),
+        concat!(
+            r#"<ac:structured-macro ac:name="code"><ac:parameter ac:name="language">rust</ac:parameter>"#,
+            "<ac:plain-text-body><![CDATA[if a < b { c[d[0]]]]><![CDATA[> }]]></ac:plain-text-body></ac:structured-macro>",
+            r#"<ac:image><ri:url ri:value="https://example.com/a.png" /></ac:image>"#,
+            r#"<ac:structured-macro ac:name="widget"><ac:parameter ac:name="url">"#,
+            r#"<ri:url ri:value="https://example.com/video" /></ac:parameter></ac:structured-macro>"#
+        )
+    );
+}
+
+#[test]
+fn storage_format_expand() {
+    assert_eq!(
+        storage_format("<details><summary><b>More</b> info</summary><p>Hidden</p></details>"),
+        concat!(
+            r#"<ac:structured-macro ac:name="expand"><ac:parameter ac:name="title">More info</ac:parameter>"#,
+            "<ac:rich-text-body><p>Hidden</p></ac:rich-text-body></ac:structured-macro>"
+        )
+    );
+}
+
+#[test]
+fn storage_format_emoji() {
+    assert_eq!(
+        storage_format("<p>Done <img data-emoji-short-name=\":tada:\" data-emoji-id=\"1f389\" data-emoji-text=\"🎉\"></p>"),
+        r#"<p>Done <ac:emoticon ac:emoji-shortname=":tada:" ac:emoji-id="1f389" ac:emoji-fallback="🎉" /></p>"#
+    );
+}
@@ -1,5 +1,12 @@
 #[allow(unused_imports)]
-use super::assert_output_json_eq;
+use super::{assert_output_json_eq, assert_output_json_eq_with_options};
+#[allow(unused_imports)]
+use crate::convert_html_str_to_adf_str;
+#[allow(unused_imports)]
+use crate::{
+    convert_html_str_to_adf_str_with_warnings, CellAccessibilityPolicy, ConversionWarning, ConverterOptions, SourcePosition,
+    TableSummaryPolicy,
+};
 
 #[allow(unused_imports)]
 use serde_json::json;
@@ -85,7 +92,12 @@ fn test_empty_cell() {
                       ]
                     },
                     {
-                      "type": "tableCell"
+                      "type": "tableCell",
+                      "content": [
+                        {
+                          "type": "paragraph"
+                        }
+                      ]
                     },
                     {
                       "type": "tableCell",
@@ -245,3 +257,917 @@ fn test_empty_cell() {
         }),
     );
 }
+
+#[test]
+fn test_cell_colwidth() {
+    assert_output_json_eq(
+        r#"<table><tr><td style="width:120px">A</td><th data-colwidth="80">B</th></tr></table>"#,
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "table",
+              "content": [
+                {
+                  "type": "tableRow",
+                  "content": [
+                    {
+                      "type": "tableCell",
+                      "attrs": {
+                        "colwidth": [120]
+                      },
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "A"
+                            }
+                          ]
+                        }
+                      ]
+                    },
+                    {
+                      "type": "tableHeader",
+                      "attrs": {
+                        "colwidth": [80]
+                      },
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "B"
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
+
+#[test]
+fn test_spanned_cell_colwidth() {
+    assert_output_json_eq(
+        r#"<table><tr><td colspan="2" data-colwidth="120,80">A</td><td colspan="2" style="width: 200px">B</td></tr></table>"#,
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "table",
+              "content": [
+                {
+                  "type": "tableRow",
+                  "content": [
+                    {
+                      "type": "tableCell",
+                      "attrs": {
+                        "colspan": 2,
+                        "colwidth": [120, 80]
+                      },
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "A"
+                            }
+                          ]
+                        }
+                      ]
+                    },
+                    {
+                      "type": "tableCell",
+                      "attrs": {
+                        "colspan": 2,
+                        "colwidth": [100, 100]
+                      },
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "B"
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
+
+#[test]
+fn test_footer_rows_last() {
+    assert_output_json_eq(
+        r"<table><tfoot><tr><td>Foot</td></tr></tfoot><tbody><tr><td>Body</td></tr></tbody></table>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "table",
+              "content": [
+                {
+                  "type": "tableRow",
+                  "content": [
+                    {
+                      "type": "tableCell",
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "Body"
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                },
+                {
+                  "type": "tableRow",
+                  "content": [
+                    {
+                      "type": "tableCell",
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "Foot"
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
+
+#[test]
+fn test_divided_cell_colwidth() {
+    assert_output_json_eq(
+        r#"<table><tr><td colspan="3" data-colwidth="100">A</td><td colspan="3" style="width: 100px">B</td><td colspan="2" data-colwidth="1,2,3">C</td></tr></table>"#,
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "table",
+              "content": [
+                {
+                  "type": "tableRow",
+                  "content": [
+                    {
+                      "type": "tableCell",
+                      "attrs": {
+                        "colspan": 3,
+                        "colwidth": [34, 33, 33]
+                      },
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "A"
+                            }
+                          ]
+                        }
+                      ]
+                    },
+                    {
+                      "type": "tableCell",
+                      "attrs": {
+                        "colspan": 3,
+                        "colwidth": [34, 33, 33]
+                      },
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "B"
+                            }
+                          ]
+                        }
+                      ]
+                    },
+                    {
+                      "type": "tableCell",
+                      "attrs": {
+                        "colspan": 2
+                      },
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "C"
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
+
+#[test]
+fn test_clamped_colspan() {
+    let converted = convert_html_str_to_adf_str(
+        r#"<table><tr><td colspan="4000000000" style="width:10px">A</td></tr></table>"#.to_string(),
+    );
+    let adf: serde_json::Value = serde_json::from_str(&converted).unwrap();
+    let attrs = &adf["content"][0]["content"][0]["content"][0]["attrs"];
+    assert_eq!(attrs["colspan"], 1000);
+    assert_eq!(attrs["colwidth"].as_array().unwrap().len(), 1000);
+}
+
+#[test]
+fn rowspan_table() {
+    assert_output_json_eq(
+        r#"<table><tr><td rowspan="2">A</td><td>B</td></tr><tr><td>C</td></tr></table>"#,
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "table",
+              "content": [
+                {
+                  "type": "tableRow",
+                  "content": [
+                    {
+                      "type": "tableCell",
+                      "attrs": {
+                        "rowspan": 2
+                      },
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "A"
+                            }
+                          ]
+                        }
+                      ]
+                    },
+                    {
+                      "type": "tableCell",
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "B"
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                },
+                {
+                  "type": "tableRow",
+                  "content": [
+                    {
+                      "type": "tableCell",
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "C"
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
+
+#[test]
+fn rowspan_cell_without_text() {
+    assert_output_json_eq(
+        r#"<table><tr><td rowspan="2"><img src="a.png"/></td><td>B</td></tr><tr><td>C</td></tr></table>"#,
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "table",
+              "content": [
+                {
+                  "type": "tableRow",
+                  "content": [
+                    {
+                      "type": "tableCell",
+                      "attrs": {
+                        "rowspan": 2
+                      },
+                      "content": [
+                        {
+                          "type": "mediaSingle",
+                          "content": [
+                            {
+                              "type": "media",
+                              "attrs": {
+                                "url": "a.png",
+                                "type": "external"
+                              }
+                            }
+                          ]
+                        }
+                      ]
+                    },
+                    {
+                      "type": "tableCell",
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "B"
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                },
+                {
+                  "type": "tableRow",
+                  "content": [
+                    {
+                      "type": "tableCell",
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "C"
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
+
+#[test]
+fn very_large_spans() {
+    let converted = convert_html_str_to_adf_str(
+        r#"<table><tr><td colspan="99999999999" rowspan="99999999999">A</td></tr><tr><td colspan="50000000">B</td></tr></table>"#
+            .to_string(),
+    );
+    let adf: serde_json::Value = serde_json::from_str(&converted).unwrap();
+    let rows = &adf["content"][0]["content"];
+    assert_eq!(rows[0]["content"][0]["attrs"], json!({"colspan": 1000, "rowspan": 65534}));
+    assert_eq!(rows[1]["content"][0]["attrs"], json!({"colspan": 1000}));
+    assert_eq!(rows[1]["content"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn rule_inside_cell() {
+    assert_output_json_eq(
+        r#"<table><tr><td>Above<hr class="divider" />Below</td><td><HR></td></tr></table>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "table",
+                    "content": [
+                        {
+                            "type": "tableRow",
+                            "content": [
+                                {
+                                    "type": "tableCell",
+                                    "content": [
+                                        {
+                                            "type": "paragraph",
+                                            "content": [
+                                                {
+                                                    "type": "text",
+                                                    "text": "Above"
+                                                }
+                                            ]
+                                        },
+                                        {
+                                            "type": "rule"
+                                        },
+                                        {
+                                            "type": "paragraph",
+                                            "content": [
+                                                {
+                                                    "type": "text",
+                                                    "text": "Below"
+                                                }
+                                            ]
+                                        }
+                                    ]
+                                },
+                                {
+                                    "type": "tableCell",
+                                    "content": [
+                                        {
+                                            "type": "rule"
+                                        }
+                                    ]
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn cell_scope_is_reported() {
+    let (_, warnings) = convert_html_str_to_adf_str_with_warnings(
+        r#"<table><tr><th scope="col">Name</th></tr><tr><td headers="name">Ada</td></tr></table>"#.to_string(),
+        &ConverterOptions::default(),
+    );
+    assert_eq!(
+        warnings,
+        vec![
+            ConversionWarning {
+                element: "th".to_string(),
+                message: "scope=\"col\" is not represented".to_string(),
+                position: Some(SourcePosition {
+                    offset: 11,
+                    line: 1,
+                    column: 12,
+                }),
+            },
+            ConversionWarning {
+                element: "td".to_string(),
+                message: "headers=\"name\" is not represented".to_string(),
+                position: Some(SourcePosition {
+                    offset: 45,
+                    line: 1,
+                    column: 46,
+                }),
+            }
+        ]
+    );
+}
+
+#[test]
+fn cell_scope_can_be_preserved() {
+    assert_output_json_eq_with_options(
+        r#"<table><tr><th scope="col">Name</th></tr></table>"#,
+        &ConverterOptions {
+            cell_accessibility_policy: CellAccessibilityPolicy::Preserve,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "table",
+                    "content": [
+                        {
+                            "type": "tableRow",
+                            "content": [
+                                {
+                                    "type": "tableHeader",
+                                    "attrs": {
+                                        "html:scope": "col"
+                                    },
+                                    "content": [
+                                        {
+                                            "type": "paragraph",
+                                            "content": [
+                                                {
+                                                    "type": "text",
+                                                    "text": "Name"
+                                                }
+                                            ]
+                                        }
+                                    ]
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn head_cells_are_headers() {
+    assert_output_json_eq(
+        "<table><thead><tr><td>Name</td><td></td></tr></thead><tbody><tr><td>Value</td><td></td></tr></tbody></table>",
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "table",
+              "content": [
+                {
+                  "type": "tableRow",
+                  "content": [
+                    {
+                      "type": "tableHeader",
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "Name"
+                            }
+                          ]
+                        }
+                      ]
+                    },
+                    {
+                      "type": "tableHeader",
+                      "content": [
+                        {
+                          "type": "paragraph"
+                        }
+                      ]
+                    }
+                  ]
+                },
+                {
+                  "type": "tableRow",
+                  "content": [
+                    {
+                      "type": "tableCell",
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "Value"
+                            }
+                          ]
+                        }
+                      ]
+                    },
+                    {
+                      "type": "tableCell",
+                      "content": [
+                        {
+                          "type": "paragraph"
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
+
+#[test]
+fn trailing_empty_cells_can_be_omitted() {
+    assert_output_json_eq_with_options(
+        "<table><tr><td></td><td>A</td><td></td><td></td></tr></table>",
+        &ConverterOptions {
+            omit_trailing_empty_cells: true,
+            ..Default::default()
+        },
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "table",
+              "content": [
+                {
+                  "type": "tableRow",
+                  "content": [
+                    {
+                      "type": "tableCell",
+                      "content": [
+                        {
+                          "type": "paragraph"
+                        }
+                      ]
+                    },
+                    {
+                      "type": "tableCell",
+                      "content": [
+                        {
+                          "type": "paragraph",
+                          "content": [
+                            {
+                              "type": "text",
+                              "text": "A"
+                            }
+                          ]
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    );
+}
+
+#[test]
+fn div_wrapped_cell_content() {
+    assert_output_json_eq(
+        "<table><tr><td><div><p>x</p></div></td><td><div>y</div><div><p>z</p><ul><li>w</li></ul></div></td></tr></table>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "table",
+                    "content": [
+                        {
+                            "type": "tableRow",
+                            "content": [
+                                {
+                                    "type": "tableCell",
+                                    "content": [
+                                        {
+                                            "type": "paragraph",
+                                            "content": [
+                                                {
+                                                    "type": "text",
+                                                    "text": "x"
+                                                }
+                                            ]
+                                        }
+                                    ]
+                                },
+                                {
+                                    "type": "tableCell",
+                                    "content": [
+                                        {
+                                            "type": "paragraph",
+                                            "content": [
+                                                {
+                                                    "type": "text",
+                                                    "text": "y"
+                                                }
+                                            ]
+                                        },
+                                        {
+                                            "type": "paragraph",
+                                            "content": [
+                                                {
+                                                    "type": "text",
+                                                    "text": "z"
+                                                }
+                                            ]
+                                        },
+                                        {
+                                            "type": "bulletList",
+                                            "content": [
+                                                {
+                                                    "type": "listItem",
+                                                    "content": [
+                                                        {
+                                                            "type": "paragraph",
+                                                            "content": [
+                                                                {
+                                                                    "type": "text",
+                                                                    "text": "w"
+                                                                }
+                                                            ]
+                                                        }
+                                                    ]
+                                                }
+                                            ]
+                                        }
+                                    ]
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn table_summary() {
+    let html = r#"<p>Before</p><table summary="Prices per region"><tr><td>EU</td></tr></table>"#;
+    let table = json!({
+        "type": "table",
+        "content": [
+            {
+                "type": "tableRow",
+                "content": [
+                    {
+                        "type": "tableCell",
+                        "content": [
+                            {
+                                "type": "paragraph",
+                                "content": [
+                                    {
+                                        "type": "text",
+                                        "text": "EU"
+                                    }
+                                ]
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    });
+    let before = json!({
+        "type": "paragraph",
+        "content": [
+            {
+                "type": "text",
+                "text": "Before"
+            }
+        ]
+    });
+    assert_output_json_eq_with_options(
+        html,
+        &ConverterOptions {
+            table_summary_policy: TableSummaryPolicy::Paragraph,
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                before,
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Prices per region"
+                        }
+                    ]
+                },
+                table
+            ]
+        }),
+    );
+    assert_output_json_eq(
+        html,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [before, table]
+        }),
+    );
+    assert_eq!(
+        convert_html_str_to_adf_str_with_warnings(html.to_string(), &ConverterOptions::default()).1,
+        vec![ConversionWarning {
+            element: "table".to_string(),
+            message: "summary=\"Prices per region\" is not represented".to_string(),
+            position: Some(SourcePosition {
+                offset: 13,
+                line: 1,
+                column: 14,
+            }),
+        }]
+    );
+}
+
+#[test]
+fn captions_keep_their_marks() {
+    let caption = json!([
+        {
+            "type": "text",
+            "text": "See "
+        },
+        {
+            "type": "text",
+            "text": "the docs",
+            "marks": [
+                {
+                    "type": "link",
+                    "attrs": {
+                        "href": "https://example.com/docs"
+                    }
+                },
+                {
+                    "type": "strong"
+                }
+            ]
+        }
+    ]);
+    assert_output_json_eq(
+        r#"<table><caption>See <b><a href="https://example.com/docs">the docs</a></b> </caption><tr><td>Cell</td></tr></table>
+        <figure><img src="a.png"><figcaption>See <b><a href="https://example.com/docs">the docs</a></b></figcaption></figure>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": caption
+                },
+                {
+                    "type": "table",
+                    "content": [
+                        {
+                            "type": "tableRow",
+                            "content": [
+                                {
+                                    "type": "tableCell",
+                                    "content": [
+                                        {
+                                            "type": "paragraph",
+                                            "content": [
+                                                {
+                                                    "type": "text",
+                                                    "text": "Cell"
+                                                }
+                                            ]
+                                        }
+                                    ]
+                                }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "type": "mediaSingle",
+                    "content": [
+                        {
+                            "type": "media",
+                            "attrs": {
+                                "url": "a.png",
+                                "type": "external"
+                            }
+                        },
+                        {
+                            "type": "caption",
+                            "content": caption
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
@@ -0,0 +1,137 @@
+#[allow(unused_imports)]
+use super::assert_output_json_eq_with_options;
+
+#[allow(unused_imports)]
+use crate::{AdfContentType, AdfMark, AdfMarkAttributes, ConverterOptions, NodeTransformer, TransformResult};
+
+#[allow(unused_imports)]
+use scraper::ElementRef;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[cfg(test)]
+struct NotePanels;
+
+#[cfg(test)]
+impl NodeTransformer for NotePanels {
+    fn transform(&self, element: ElementRef, default: Option<AdfContentType>) -> TransformResult {
+        let is_note = element.value().name() == "div"
+            && element.value().classes().any(|class| class == "note");
+        if is_note {
+            assert!(default.is_none());
+            TransformResult::Replace(AdfContentType::from_name_and_attributes("panel", |_| {
+                vec![("panelType".to_string(), json!("note"))]
+            }))
+        } else {
+            TransformResult::Default
+        }
+    }
+}
+
+#[cfg(test)]
+struct ResolvedComments;
+
+#[cfg(test)]
+impl NodeTransformer for ResolvedComments {
+    fn transform(&self, element: ElementRef, _default: Option<AdfContentType>) -> TransformResult {
+        if element.value().classes().any(|class| class == "resolved-comment") {
+            TransformResult::Replace(AdfContentType::from_name_and_marks("text", &[AdfMark {
+                typename: "annotation".to_string(),
+                attributes: AdfMarkAttributes::Map(vec![
+                    ("id".to_string(), json!("comment-1")),
+                    ("annotationType".to_string(), json!("inlineComment")),
+                    ("__resolved".to_string(), json!(true)),
+                ]),
+            }]))
+        } else {
+            TransformResult::Default
+        }
+    }
+}
+
+#[test]
+fn transformer_creates_panels() {
+    assert_output_json_eq_with_options(
+        r#"<div class="note"><p>Careful <b>now</b></p></div><div><p>Plain</p></div>"#,
+        &ConverterOptions::default().with_node_transformer(NotePanels),
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "panel",
+              "attrs": {
+                "panelType": "note"
+              },
+              "content": [
+                {
+                  "type": "paragraph",
+                  "content": [
+                    {
+                      "type": "text",
+                      "text": "Careful "
+                    },
+                    {
+                      "type": "text",
+                      "text": "now",
+                      "marks": [
+                        {
+                          "type": "strong"
+                        }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            },
+            {
+              "type": "paragraph",
+              "content": [
+                {
+                  "type": "text",
+                  "text": "Plain"
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
+
+#[test]
+fn transformer_marks_with_boolean_attributes() {
+    assert_output_json_eq_with_options(
+        r#"<p>See <span class="resolved-comment">this</span></p>"#,
+        &ConverterOptions::default().with_node_transformer(ResolvedComments),
+        json!({
+          "version": 1,
+          "type": "doc",
+          "content": [
+            {
+              "type": "paragraph",
+              "content": [
+                {
+                  "type": "text",
+                  "text": "See "
+                },
+                {
+                  "type": "text",
+                  "text": "this",
+                  "marks": [
+                    {
+                      "type": "annotation",
+                      "attrs": {
+                        "id": "comment-1",
+                        "annotationType": "inlineComment",
+                        "__resolved": true
+                      }
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }),
+    )
+}
@@ -0,0 +1,259 @@
+#[allow(unused_imports)]
+use super::{assert_output_json_eq, assert_output_json_eq_with_options};
+
+#[allow(unused_imports)]
+use crate::ConverterOptions;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[cfg(test)]
+#[allow(dead_code)]
+fn with_base_url() -> ConverterOptions {
+    ConverterOptions {
+        base_url: Some(url::Url::parse("https://example.com/docs/page.html").unwrap()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn relative_urls_are_resolved() {
+    assert_output_json_eq_with_options(
+        r#"<p><a href="guide.html">Guide</a> <a href="https://other.com">Other</a></p><img src="/img/a.png"><iframe src="embed/1"></iframe>"#,
+        &with_base_url(),
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Guide",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://example.com/docs/guide.html"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "Other",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://other.com"
+                                    }
+                                }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "type": "mediaSingle",
+                    "content": [
+                        {
+                            "type": "media",
+                            "attrs": {
+                                "url": "https://example.com/img/a.png",
+                                "type": "external"
+                            }
+                        }
+                    ]
+                },
+                {
+                    "type": "embedCard",
+                    "attrs": {
+                        "url": "https://example.com/docs/embed/1",
+                        "layout": "center"
+                    }
+                }
+            ]
+        }),
+    )
+}
+
+#[test]
+fn protocol_relative_urls_are_resolved() {
+    assert_output_json_eq_with_options(
+        r#"<p><a href="//cdn.example.org/file">CDN</a></p>"#,
+        &with_base_url(),
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "CDN",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://cdn.example.org/file"
+                                    }
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    )
+}
+
+#[test]
+fn relative_urls_are_kept_without_a_base_url() {
+    assert_output_json_eq(
+        r#"<p><a href="guide.html">Guide</a></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Guide",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "guide.html"
+                                    }
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    )
+}
+
+#[test]
+fn tracking_params_are_stripped() {
+    assert_output_json_eq_with_options(
+        r#"<p><a href="https://example.com/item?utm_source=news&id=42&utm_medium=email#top">Item</a> <a href="/page?ref=home">Page</a></p>"#,
+        &ConverterOptions {
+            stripped_query_params: vec!["utm_*".to_string(), "ref".to_string()],
+            ..Default::default()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Item",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://example.com/item?id=42#top"
+                                    }
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "Page",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "/page"
+                                    }
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    )
+}
+
+#[test]
+fn fragment_links_are_kept() {
+    assert_output_json_eq_with_options(
+        r##"<p><a href="#intro">Intro</a></p>"##,
+        &with_base_url(),
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Intro",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "#intro"
+                                    }
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    )
+}
+
+#[test]
+fn fragment_links_can_be_resolved() {
+    assert_output_json_eq_with_options(
+        r##"<p><a href="#intro">Intro</a></p>"##,
+        &ConverterOptions {
+            resolve_fragment_links: true,
+            ..with_base_url()
+        },
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Intro",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "https://example.com/docs/page.html#intro"
+                                    }
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    )
+}
@@ -0,0 +1,387 @@
+#[allow(unused_imports)]
+use super::assert_output_json_eq;
+
+#[allow(unused_imports)]
+use crate::{
+    convert_html_str_to_adf_str_with_stats, convert_html_str_to_adf_str_with_warnings, ConversionStats,
+    ConversionWarning, ConverterOptions, EditMetadataPolicy, SourcePosition,
+};
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[cfg(test)]
+#[allow(dead_code)]
+fn warnings_for(html: &str, options: &ConverterOptions) -> Vec<ConversionWarning> {
+    convert_html_str_to_adf_str_with_warnings(html.to_string(), options).1
+}
+
+#[test]
+fn comments_are_stripped() {
+    assert_output_json_eq(
+        "<p>One</p><!-- hidden note --><p>Two <!-- inline --></p>",
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "One"
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Two"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+    assert!(warnings_for("<p>One</p><!-- hidden note -->", &ConverterOptions::default()).is_empty());
+}
+
+#[test]
+fn comments_are_captured() {
+    let options = ConverterOptions {
+        capture_comments: true,
+        ..Default::default()
+    };
+    let (adf, warnings) = convert_html_str_to_adf_str_with_warnings(
+        "<p>One</p><!-- hidden note --><p>Two</p>".to_string(),
+        &options,
+    );
+    assert!(!adf.contains("hidden note"));
+    assert_eq!(
+        warnings,
+        vec![ConversionWarning {
+            element: "#comment".to_string(),
+            message: "hidden note".to_string(),
+            position: Some(SourcePosition {
+                offset: 10,
+                line: 1,
+                column: 11,
+            }),
+        }]
+    );
+}
+
+#[test]
+fn ignored_attribute_hooks_are_reported() {
+    let options = ConverterOptions::default()
+        .with_attribute_hook("td", |_| vec![])
+        .with_attribute_hook("a", |_| vec![])
+        .with_attribute_hook("br", |_| vec![]);
+    assert_eq!(
+        warnings_for("<p>One</p>", &options),
+        vec![
+            ConversionWarning {
+                element: "a".to_string(),
+                message: "attribute hook ignored, this element carries no attributes".to_string(),
+                position: None,
+            },
+            ConversionWarning {
+                element: "br".to_string(),
+                message: "attribute hook ignored, this element carries no attributes".to_string(),
+                position: None,
+            }
+        ]
+    );
+}
+
+#[test]
+fn right_to_left_text_is_kept() {
+    let html = r#"<p dir="rtl">שלום <b>עולם</b></p><p dir="RTL">مرحبا بالعالم</p>"#;
+    assert_output_json_eq(
+        html,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "שלום "
+                        },
+                        {
+                            "type": "text",
+                            "text": "עולם",
+                            "marks": [
+                                {
+                                    "type": "strong"
+                                }
+                            ]
+                        }
+                    ]
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "مرحبا بالعالم"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+    assert_eq!(
+        warnings_for(html, &ConverterOptions::default()),
+        vec![
+            ConversionWarning {
+                element: "p".to_string(),
+                message: "right-to-left text direction is not represented".to_string(),
+                position: Some(SourcePosition {
+                    offset: 0,
+                    line: 1,
+                    column: 1,
+                }),
+            },
+            ConversionWarning {
+                element: "p".to_string(),
+                message: "right-to-left text direction is not represented".to_string(),
+                // The Hebrew before it takes two bytes per character
+                position: Some(SourcePosition {
+                    offset: 41,
+                    line: 1,
+                    column: 34,
+                }),
+            }
+        ]
+    );
+}
+
+#[test]
+fn edit_metadata_is_reported() {
+    let html = r#"<p><del datetime="2024-01-01T10:00Z" cite="https://example.com/why">old</del> <ins datetime="2024-01-02">new</ins></p>"#;
+    assert_eq!(
+        warnings_for(html, &ConverterOptions::default()),
+        vec![
+            ConversionWarning {
+                element: "del".to_string(),
+                message: r#"datetime="2024-01-01T10:00Z" is not represented"#.to_string(),
+                position: Some(SourcePosition {
+                    offset: 3,
+                    line: 1,
+                    column: 4,
+                }),
+            },
+            ConversionWarning {
+                element: "del".to_string(),
+                message: r#"cite="https://example.com/why" is not represented"#.to_string(),
+                position: Some(SourcePosition {
+                    offset: 3,
+                    line: 1,
+                    column: 4,
+                }),
+            },
+            ConversionWarning {
+                element: "ins".to_string(),
+                message: r#"datetime="2024-01-02" is not represented"#.to_string(),
+                position: Some(SourcePosition {
+                    offset: 78,
+                    line: 1,
+                    column: 79,
+                }),
+            }
+        ]
+    );
+    assert_eq!(
+        warnings_for(
+            html,
+            &ConverterOptions {
+                edit_metadata_policy: EditMetadataPolicy::Ignore,
+                ..Default::default()
+            }
+        ),
+        vec![]
+    );
+    assert_output_json_eq(
+        html,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "old",
+                            "marks": [
+                                {
+                                    "type": "strike"
+                                }
+                            ]
+                        },
+                        {
+                            "type": "text",
+                            "text": " "
+                        },
+                        {
+                            "type": "text",
+                            "text": "new",
+                            "marks": [
+                                {
+                                    "type": "underline"
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn bidirectional_text_is_kept() {
+    let html = r#"<p>Reads <bdo dir="rtl">abc אבג</bdo> by <bdi>إيان</bdi></p>"#;
+    assert_output_json_eq(
+        html,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Reads "
+                        },
+                        {
+                            "type": "text",
+                            "text": "abc אבג"
+                        },
+                        {
+                            "type": "text",
+                            "text": " by "
+                        },
+                        {
+                            "type": "text",
+                            "text": "إيان"
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+    let options = ConverterOptions {
+        capture_direction_overrides: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        warnings_for(html, &options),
+        vec![ConversionWarning {
+            element: "bdo".to_string(),
+            message: "text direction override is not represented, text keeps its source order".to_string(),
+            position: Some(SourcePosition {
+                offset: 9,
+                line: 1,
+                column: 10,
+            }),
+        }]
+    );
+}
+
+#[test]
+fn warnings_carry_source_positions() {
+    let html = "<p>One</p>\n<p>Two <!-- first -->\n  <script>'<!-- not a comment -->'</script><span dir=rtl>three</span></p>";
+    let options = ConverterOptions {
+        capture_comments: true,
+        ..Default::default()
+    };
+    let positions: Vec<Option<SourcePosition>> = warnings_for(html, &options)
+        .into_iter()
+        .map(|warning| warning.position)
+        .collect();
+    assert_eq!(
+        positions,
+        vec![
+            Some(SourcePosition {
+                offset: 18,
+                line: 2,
+                column: 8,
+            }),
+            Some(SourcePosition {
+                offset: 76,
+                line: 3,
+                column: 44,
+            })
+        ]
+    );
+    assert_eq!(&html[76..81], "<span");
+}
+
+#[test]
+fn warnings_for_elements_the_parser_adds_have_no_position() {
+    // <b> is reopened within the second paragraph, so there are more <b> elements than tags
+    let html = r#"<p><b dir="rtl">One</p><p>Two</p>"#;
+    let warnings = warnings_for(html, &ConverterOptions::default());
+    assert!(!warnings.is_empty());
+    assert!(warnings.iter().all(|warning| warning.position.is_none()));
+}
+
+#[test]
+fn conversion_stats() {
+    let html = "<h1>Title</h1><!-- draft --><p>One <b>two</b></p><ul><li>A</li><li>B</li></ul><style>p {}</style>\
+        <div aria-hidden=\"true\"><i>icon</i></div>";
+    let options = ConverterOptions {
+        capture_comments: true,
+        exclude_aria_hidden: true,
+        ..Default::default()
+    };
+    let (converted, stats) = convert_html_str_to_adf_str_with_stats(html.to_string(), &options);
+    assert_eq!(converted, crate::convert_html_str_to_adf_str_with_options(html.to_string(), &options));
+    assert_eq!(
+        stats,
+        ConversionStats {
+            node_count: 13,
+            dropped_count: 3,
+            warning_count: 1,
+            per_type_counts: [
+                ("bulletList", 1),
+                ("doc", 1),
+                ("heading", 1),
+                ("listItem", 2),
+                ("paragraph", 3),
+                ("text", 5),
+            ]
+            .iter()
+            .map(|(node_type, count)| (node_type.to_string(), *count))
+            .collect(),
+        }
+    );
+}
+
+#[test]
+fn truncated_documents_are_reported() {
+    let html = "<p>One</p><p>Two</p><p>Three</p>";
+    let options = ConverterOptions {
+        max_output_nodes: Some(3),
+        ..Default::default()
+    };
+    let (converted, warnings) = convert_html_str_to_adf_str_with_warnings(html.to_string(), &options);
+    assert!(!converted.contains("Three"));
+    assert_eq!(
+        warnings,
+        vec![ConversionWarning {
+            element: "#document".to_string(),
+            message: "document truncated at 3 nodes".to_string(),
+            position: None,
+        }]
+    );
+    assert_eq!(convert_html_str_to_adf_str_with_stats(html.to_string(), &options).1.warning_count, 1);
+    assert!(warnings_for(html, &ConverterOptions::default()).is_empty());
+}
@@ -0,0 +1,141 @@
+#[allow(unused_imports)]
+use super::assert_output_json_eq;
+
+#[allow(unused_imports)]
+use serde_json::json;
+
+#[cfg(test)]
+#[test]
+fn self_closing_void_elements() {
+    assert_output_json_eq(
+        r#"<p>One<br/>Two<br />Three<BR/>Four</p><hr /><img src="a.png"/><img src="b.png" />"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "One"
+                        },
+                        {
+                            "type": "hardBreak"
+                        },
+                        {
+                            "type": "text",
+                            "text": "Two"
+                        },
+                        {
+                            "type": "hardBreak"
+                        },
+                        {
+                            "type": "text",
+                            "text": "Three"
+                        },
+                        {
+                            "type": "hardBreak"
+                        },
+                        {
+                            "type": "text",
+                            "text": "Four"
+                        }
+                    ]
+                },
+                {
+                    "type": "rule"
+                },
+                {
+                    "type": "mediaSingle",
+                    "content": [
+                        {
+                            "type": "media",
+                            "attrs": {
+                                "url": "a.png",
+                                "type": "external"
+                            }
+                        }
+                    ]
+                },
+                {
+                    "type": "mediaSingle",
+                    "content": [
+                        {
+                            "type": "media",
+                            "attrs": {
+                                "url": "b.png",
+                                "type": "external"
+                            }
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn self_closing_elements_do_not_swallow_what_follows() {
+    assert_output_json_eq(
+        r#"<iframe src="https://example.com/video"/><p><a name="top"/>Text <span class="icon" />and <a href=/path/>link</a></p>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "embedCard",
+                    "attrs": {
+                        "url": "https://example.com/video",
+                        "layout": "center"
+                    }
+                },
+                {
+                    "type": "paragraph",
+                    "content": [
+                        {
+                            "type": "text",
+                            "text": "Text "
+                        },
+                        {
+                            "type": "text",
+                            "text": "and "
+                        },
+                        {
+                            "type": "text",
+                            "text": "link",
+                            "marks": [
+                                {
+                                    "type": "link",
+                                    "attrs": {
+                                        "href": "/path/"
+                                    }
+                                }
+                            ]
+                        }
+                    ]
+                }
+            ]
+        }),
+    );
+}
+
+#[test]
+fn iframe_fallback_text() {
+    assert_output_json_eq(
+        r#"<iframe src="https://example.com/video">Your browser does not support iframes</iframe>"#,
+        json!({
+            "version": 1,
+            "type": "doc",
+            "content": [
+                {
+                    "type": "embedCard",
+                    "attrs": {
+                        "url": "https://example.com/video",
+                        "layout": "center"
+                    }
+                }
+            ]
+        }),
+    );
+}
@@ -1,34 +1,63 @@
+use crate::adf_structure::ARIA_HEADING_TYPE;
+use crate::adf_structure::DATE_TYPE;
+use crate::adf_structure::EMOJI_TYPE;
 use crate::adf_structure::EMPTY_CHILD_TYPE;
 use crate::adf_structure::LEGAL_CHILD_TYPES;
 use crate::adf_structure::NODE_MAP;
+use crate::adf_structure::PANEL_TYPE;
 
 use scraper::ElementRef;
 use serde_json::Value;
+use std::borrow::Cow;
 
+use crate::dates::time_element_timestamp;
+use crate::types::converter_options::ConverterOptions;
+use crate::types::node_transformer::TransformResult;
+
+/// The attributes of a mark, as (name, value) pairs.
 #[derive(Clone)]
 pub enum AdfMarkAttributes {
+    /// The same attributes for every element.
     List(Vec<(String, String)>),
+    /// Attributes read from the element the mark is applied for, e.g. the href of a link.
     Generator(fn(&ElementRef) -> Vec<(String, String)>),
+    /// The same attributes for every element, of any JSON type (e.g. booleans), kept in the order given.
+    Map(Vec<(String, Value)>),
 }
 
+/// A mark applied to all text within an element, e.g. `strong` for `<b>`.
 #[derive(Clone)]
 pub struct AdfMark {
+    /// The ADF mark type, e.g. "strong" or "link".
     pub typename: String,
     pub attributes: AdfMarkAttributes,
 }
 
-type AttributeExtractor = Option<fn(&ElementRef) -> Vec<(String, Value)>>;
-type ChildrenExtractor = Option<fn(&ElementRef) -> (Vec<(String, Value)>, Vec<Value>)>;
+/// Generates the attributes of a node from the element it is converted from.
+pub type AttributeExtractorFn = fn(&ElementRef) -> Vec<(String, Value)>;
+/// Generates the attributes of a node along with its child nodes (as ADF JSON) from the element it is converted from.
+pub type ChildrenExtractorFn = fn(&ElementRef) -> (Vec<(String, Value)>, Vec<Value>);
 
+/// Describes how an HTML element is converted: into which ADF node type, carrying which marks and attributes.
 #[derive(Clone, Default)]
 pub struct AdfContentType {
+    /// The ADF node type, e.g. "paragraph", or "text" for elements that only contribute marks.
     pub typename: String,
+    /// Marks applied to all text within the element.
     pub marks: Vec<AdfMark>,
-    pub attributes: AttributeExtractor,
-    pub children: ChildrenExtractor,
+    pub attributes: Option<AttributeExtractorFn>,
+    pub children: Option<ChildrenExtractorFn>,
 }
 
 impl AdfContentType {
+    /// A node type without marks or attributes.
+    ///
+    /// ```rust
+    /// use htmltoadf::AdfContentType;
+    ///
+    /// let paragraph = AdfContentType::from_name("paragraph");
+    /// assert_eq!(paragraph.typename, "paragraph");
+    /// ```
     pub fn from_name(typename: &str) -> Self {
         Self {
             typename: typename.to_string(),
@@ -36,6 +65,17 @@ impl AdfContentType {
         }
     }
 
+    /// A node type whose text carries the given marks. Inline elements use the "text" type.
+    ///
+    /// ```rust
+    /// use htmltoadf::{AdfContentType, AdfMark, AdfMarkAttributes};
+    ///
+    /// let bold = AdfContentType::from_name_and_marks("text", &[AdfMark {
+    ///     typename: "strong".to_string(),
+    ///     attributes: AdfMarkAttributes::List(vec![]),
+    /// }]);
+    /// assert_eq!(bold.marks[0].typename, "strong");
+    /// ```
     pub fn from_name_and_marks(typename: &str, marks: &[AdfMark]) -> Self {
         Self {
             typename: typename.to_string(),
@@ -44,9 +84,21 @@ impl AdfContentType {
         }
     }
 
+    /// A node type whose attributes are generated from each element,
+    /// by a function taking the element and returning (name, value) pairs.
+    ///
+    /// ```rust
+    /// use htmltoadf::AdfContentType;
+    /// use serde_json::json;
+    ///
+    /// let panel = AdfContentType::from_name_and_attributes("panel", |_element| {
+    ///     vec![("panelType".to_string(), json!("info"))]
+    /// });
+    /// assert!(panel.attributes.is_some());
+    /// ```
     pub fn from_name_and_attributes(
         typename: &str,
-        attributes: fn(&ElementRef) -> Vec<(String, Value)>,
+        attributes: AttributeExtractorFn,
     ) -> Self {
         Self {
             typename: typename.to_string(),
@@ -54,10 +106,54 @@ impl AdfContentType {
             ..Default::default()
         }
     }
-    
+
+    /// A node type carrying both marks and generated attributes, see `from_name_and_marks`
+    /// and `from_name_and_attributes`.
+    ///
+    /// ```rust
+    /// use htmltoadf::{AdfContentType, AdfMark, AdfMarkAttributes};
+    /// use serde_json::json;
+    ///
+    /// let highlighted = AdfContentType::from_name_and_marks_and_attributes(
+    ///     "text",
+    ///     &[AdfMark {
+    ///         typename: "strong".to_string(),
+    ///         attributes: AdfMarkAttributes::List(vec![]),
+    ///     }],
+    ///     |_element| vec![("localId".to_string(), json!("highlight"))],
+    /// );
+    /// assert_eq!(highlighted.marks.len(), 1);
+    /// assert!(highlighted.attributes.is_some());
+    /// ```
+    pub fn from_name_and_marks_and_attributes(
+        typename: &str,
+        marks: &[AdfMark],
+        attributes: AttributeExtractorFn,
+    ) -> Self {
+        Self {
+            typename: typename.to_string(),
+            marks: marks.to_vec(),
+            attributes: Some(attributes),
+            ..Default::default()
+        }
+    }
+
+    /// A node type that generates its own child nodes, by a function taking the element and returning
+    /// the node's attributes along with its children as ADF JSON. Images use this for their media node.
+    ///
+    /// ```rust
+    /// use htmltoadf::AdfContentType;
+    /// use serde_json::json;
+    ///
+    /// let media = AdfContentType::from_name_and_children("mediaSingle", |element| {
+    ///     let url = element.value().attr("src").unwrap_or_default();
+    ///     (vec![], vec![json!({"type": "media", "attrs": {"type": "external", "url": url}})])
+    /// });
+    /// assert!(media.children.is_some());
+    /// ```
     pub fn from_name_and_children(
         typename: &str,
-        children_extractor: fn(&ElementRef) -> (Vec<(String, Value)>, Vec<Value>),
+        children_extractor: ChildrenExtractorFn,
     ) -> Self {
         Self {
             typename: typename.to_string(),
@@ -67,11 +163,86 @@ impl AdfContentType {
     }
 }
 
-pub fn content_type_for_node_type(nodetype: &str) -> &AdfContentType {
-    lazy_static! {
-        pub static ref TEXT_TYPE: AdfContentType = AdfContentType::from_name("text");
-    };
-    return NODE_MAP.get(nodetype).unwrap_or(&TEXT_TYPE);
+lazy_static! {
+    /**
+     * The content type of elements without a mapping of their own, which only contribute their text.
+     */
+    static ref TEXT_TYPE: AdfContentType = AdfContentType::from_name("text");
+}
+
+pub fn content_type_for_node_type(nodetype: &str) -> &'static AdfContentType {
+    NODE_MAP.get(nodetype).unwrap_or(&TEXT_TYPE)
+}
+
+/**
+ * Look up the built in content type for an element, taking into account attributes
+ * that change its meaning (such as role="heading"). None for elements without a mapping.
+ */
+fn mapped_content_type(element: &ElementRef) -> Option<&'static AdfContentType> {
+    if element
+        .value()
+        .attr("role")
+        .is_some_and(|role| role.eq_ignore_ascii_case("heading"))
+    {
+        return Some(&ARIA_HEADING_TYPE);
+    }
+    if element.value().name() == "img" && element.value().attr("data-emoji-short-name").is_some() {
+        return Some(&EMOJI_TYPE);
+    }
+    if time_element_timestamp(element).is_some() {
+        return Some(&DATE_TYPE);
+    }
+    if element.value().name() == "td" && is_in_table_head(element) {
+        return NODE_MAP.get("th");
+    }
+    NODE_MAP.get(element.value().name())
+}
+
+/**
+ * Returns true for a cell in a row of the table's <thead>, which is a header whether authored as <td> or <th>.
+ */
+fn is_in_table_head(cell: &ElementRef) -> bool {
+    cell.ancestors()
+        .filter_map(ElementRef::wrap)
+        .take_while(|ancestor| ancestor.value().name() != "table")
+        .any(|ancestor| ancestor.value().name() == "thead")
+}
+
+/**
+ * Look up the built in content type for an element, see mapped_content_type.
+ */
+pub fn content_type_for_element(element: &ElementRef) -> &'static AdfContentType {
+    mapped_content_type(element).unwrap_or(&TEXT_TYPE)
+}
+
+/**
+ * Look up the content type for an element. Any registered node transformers have the first say,
+ * then the built in mapping applies, under which unmapped elements only contribute their text.
+ */
+pub fn transformed_content_type(element: &ElementRef, options: &ConverterOptions) -> Cow<'static, AdfContentType> {
+    let default = mapped_content_type(element).or_else(|| panel_type(element, options).map(|_| &*PANEL_TYPE));
+    options
+        .node_transformers
+        .iter()
+        .find_map(|transformer| match transformer.transform(*element, default.cloned()) {
+            TransformResult::Replace(content_type) => Some(Cow::Owned(content_type)),
+            TransformResult::Default => None,
+        })
+        .unwrap_or(Cow::Borrowed(default.unwrap_or(&TEXT_TYPE)))
+}
+
+/**
+ * The panel type of a <div>, from the first of the panel_classes patterns to match any of its classes.
+ */
+pub fn panel_type<'a>(element: &ElementRef, options: &'a ConverterOptions) -> Option<&'a str> {
+    if element.value().name() != "div" {
+        return None;
+    }
+    options
+        .panel_classes
+        .iter()
+        .find(|(pattern, _)| element.value().classes().any(|class| pattern.is_match(class)))
+        .map(|(_, panel_type)| panel_type.as_str())
 }
 
 pub fn is_valid_child_type(parent_typename: &str, child_typename: &str, index: usize) -> bool {
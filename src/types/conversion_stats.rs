@@ -0,0 +1,34 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+/**
+ * Counts summarizing a conversion, to gauge how faithfully a body of HTML converts.
+ */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConversionStats {
+    /// The number of nodes in the converted document, counting the doc itself.
+    pub node_count: usize,
+    /// The number of source elements left out of the document along with everything inside of them,
+    /// such as scripts, or elements outside of `allowed_tags` under `UnknownElementPolicy::Drop`.
+    pub dropped_count: usize,
+    /// The number of warnings the conversion produced (see `convert_html_str_to_adf_str_with_warnings`).
+    pub warning_count: usize,
+    /// The number of nodes of each type in the converted document.
+    pub per_type_counts: BTreeMap<String, usize>,
+}
+
+impl ConversionStats {
+    /**
+     * Count a converted node, and all of the nodes within it.
+     */
+    pub fn count_nodes(&mut self, node: &Value) {
+        if let Some(node_type) = node["type"].as_str() {
+            self.node_count += 1;
+            *self.per_type_counts.entry(node_type.to_string()).or_default() += 1;
+        }
+        if let Some(content) = node["content"].as_array() {
+            content.iter().for_each(|child| self.count_nodes(child));
+        }
+    }
+}
@@ -0,0 +1,41 @@
+/**
+ * Describes something in the source HTML that could not be represented in ADF,
+ * and was dropped or altered during conversion.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConversionWarning {
+    /// The element the warning relates to, e.g. "td", or "#comment" for comments.
+    pub element: String,
+    /// A human readable description of the warning.
+    pub message: String,
+    /// Where the element (or comment) starts in the source HTML, when it can be located.
+    pub position: Option<SourcePosition>,
+}
+
+/**
+ * A location within the source HTML.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourcePosition {
+    /// The byte offset from the start of the HTML.
+    pub offset: usize,
+    /// The line, counting from 1.
+    pub line: usize,
+    /// The column within the line in characters, counting from 1.
+    pub column: usize,
+}
+
+impl SourcePosition {
+    /**
+     * The position of the given byte offset within some HTML.
+     */
+    pub fn from_offset(html: &str, offset: usize) -> Self {
+        let preceding = &html[..offset];
+        let line_start = preceding.rfind('\n').map(|index| index + 1).unwrap_or(0);
+        Self {
+            offset,
+            line: preceding.matches('\n').count() + 1,
+            column: preceding[line_start..].chars().count() + 1,
+        }
+    }
+}
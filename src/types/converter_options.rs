@@ -0,0 +1,334 @@
+use regex::Regex;
+use scraper::{ElementRef, Selector};
+use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
+use url::Url;
+
+use crate::types::node_transformer::NodeTransformer;
+
+/**
+ * Options controlling how HTML is converted into ADF.
+ * Start from `ConverterOptions::default()` and override the fields you need.
+ */
+#[derive(Clone, Debug)]
+pub struct ConverterOptions {
+    /// Generated heading levels are clamped into `1..=max_heading_level`.
+    pub max_heading_level: u8,
+    /// Keep the `id` of headings (the target of in-page anchors) as their `localId` attribute.
+    pub capture_heading_ids: bool,
+    /// Mark the expands of `<details open>` with `"__expanded": true`, a hint some consumers read to
+    /// render them open. ADF itself does not define it.
+    pub capture_expanded_state: bool,
+    /// When set, only these tag names are converted. `html` and `body` are always permitted.
+    pub allowed_tags: Option<Vec<String>>,
+    /// How elements outside of `allowed_tags` are handled.
+    pub unknown_element_policy: UnknownElementPolicy,
+    /// Elements recognized as the root of a rich text editor, such as `div.ProseMirror`. They are always
+    /// unwrapped, whatever `allowed_tags` says, so their content converts as if it stood on its own.
+    pub editor_wrappers: Vec<Selector>,
+    /// A `<div>` with a class matching one of these patterns becomes a panel of the paired `panelType`
+    /// (`info`, `note`, `tip`, `warning`, `error` or `success`), e.g. `(Regex::new("warning")?, "warning")`
+    /// for `<div class="alert alert-warning">`. The first matching pattern wins, other divs are unwrapped as usual.
+    pub panel_classes: Vec<(Regex, String)>,
+    /// HTML comments never appear in the document. When set they are reported as warnings.
+    pub capture_comments: bool,
+    /// `<bdo>` overrides the direction of its text, which is kept in its source order. When set each
+    /// override is reported as a warning.
+    pub capture_direction_overrides: bool,
+    /// Leave the line numbers syntax highlighters render out of code blocks: the text of gutter elements
+    /// (such as `<span class="linenos">`), and in a `<pre data-line-numbers>` the number starting each line.
+    pub strip_line_numbers: bool,
+    /// Convert `backtick` delimited spans within text into text with a code mark.
+    pub markdown_inline_code: bool,
+    /// Convert line breaks within text (outside of `<pre>`) into hard breaks, as for pasted plain text.
+    /// Otherwise they collapse into a space, as a browser would render them.
+    pub preserve_line_breaks_in_paragraphs: bool,
+    /// Give the text of `<small>` elements a gray text color, rather than dropping the sizing entirely.
+    pub muted_small_text: bool,
+    /// Give the text of `<abbr>` elements an em mark. By default only their text is kept.
+    pub italic_abbreviations: bool,
+    /// Give text styled with a monospace `font-family` (such as Courier, Consolas or Menlo) a code mark,
+    /// as code pasted from some editors arrives without a `<code>` element.
+    pub monospace_code: bool,
+    /// Text longer than this many characters is split into several adjacent text nodes
+    /// carrying the same marks. Splits never fall within a grapheme.
+    pub max_text_node_len: Option<usize>,
+    /// The `version` of the document, 1 being the only version of ADF so far.
+    pub adf_version: u64,
+    /// Top-level fields added to the document after its content, e.g. `("localId".to_string(), json!("page-1"))`.
+    /// Fields named `version`, `type` or `content` are ignored.
+    pub doc_metadata: Vec<(String, Value)>,
+    /// Stop converting once the document has more than this many nodes (counting the doc itself),
+    /// guarding against hostile input.
+    /// `try_convert_html_str_to_adf_str` then fails with `HtmlToAdfError::TooLarge`,
+    /// while the other conversions return the document up to that point, with a warning from
+    /// `convert_html_str_to_adf_str_with_warnings`.
+    /// The limit is checked between leaves, so a leaf converting into many nodes (such as long text
+    /// split by `max_text_node_len`) can take the document past it.
+    pub max_output_nodes: Option<usize>,
+    /// Keep every rule of a run of adjacent `<hr>` elements. By default a run becomes a single rule.
+    pub keep_adjacent_rules: bool,
+    /// Remove blank paragraphs (only whitespace or line breaks) from the start and end of the document,
+    /// as left by whitespace around the content. Blank paragraphs in between are kept.
+    pub trim_document: bool,
+    /// End a paragraph at two or more consecutive `<br>`, as a blank line between them reads as a new paragraph.
+    /// By default they are kept as hard breaks within the one paragraph.
+    pub split_paragraphs_at_double_breaks: bool,
+    /// Turn every four non-breaking spaces at the start of a top level paragraph, as word processors indent with,
+    /// into a level of indentation (at most 6) rather than keeping them as spaces.
+    pub nbsp_indentation: bool,
+    /// Give a document without any content a single empty paragraph, as some ADF consumers reject a document
+    /// without a block. Otherwise its content is left empty.
+    pub empty_document_paragraph: bool,
+    /// Leave out the cells without content at the end of each table row.
+    pub omit_trailing_empty_cells: bool,
+    /// How `<ol reversed>` lists, which count down, are converted.
+    pub reversed_list_policy: ReversedListPolicy,
+    /// How list items numbered with `<li value>`, which ADF has no per item equivalent for, are converted.
+    pub list_item_value_policy: ListItemValuePolicy,
+    /// How the descriptive `summary` attribute of tables, which ADF has no place for, is handled.
+    pub table_summary_policy: TableSummaryPolicy,
+    /// How the `scope` and `headers` attributes of table cells, which ADF does not model, are handled.
+    pub cell_accessibility_policy: CellAccessibilityPolicy,
+    /// How the `datetime` and `cite` attributes of `<del>` and `<ins>`, which ADF has no place for, are handled.
+    pub edit_metadata_policy: EditMetadataPolicy,
+    /// Indent the JSON output rather than emitting it on a single line.
+    pub pretty_json: bool,
+    /// The order marks are listed in on each text node.
+    pub mark_order: MarkOrder,
+    /// How form controls (`input`, `textarea`, `button`, `select`) are handled.
+    pub form_field_policy: FormFieldPolicy,
+    /// Ignore elements marked `aria-hidden="true"` (typically decorative icons) and everything inside of them.
+    pub exclude_aria_hidden: bool,
+    /// Ignore elements styled `display: none` inline, and everything inside of them.
+    pub exclude_display_none: bool,
+    /// When set, relative link, image and embed URLs are resolved against this URL.
+    pub base_url: Option<Url>,
+    /// Also resolve fragment-only links (`#section`) against the base URL. By default they are kept as they are,
+    /// as links within the document.
+    pub resolve_fragment_links: bool,
+    /// Query parameters removed from link, image and embed URLs, e.g. `"ref"`.
+    /// A trailing `*` matches by prefix, so `"utm_*"` removes `utm_source`, `utm_medium` etc.
+    pub stripped_query_params: Vec<String>,
+    /// User supplied attribute generators, see `with_attribute_hook`.
+    pub attribute_hooks: Vec<AttributeHook>,
+    /// User supplied element conversions, see `with_node_transformer`.
+    pub node_transformers: Vec<Arc<dyn NodeTransformer>>,
+}
+
+type AttributeHookFn = dyn Fn(&ElementRef) -> Vec<(String, Value)> + Send + Sync;
+
+/**
+ * A user supplied attribute generator for a given tag name.
+ */
+#[derive(Clone)]
+pub struct AttributeHook {
+    pub tag: String,
+    pub hook: Arc<AttributeHookFn>,
+}
+
+impl fmt::Debug for AttributeHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "AttributeHook({})", self.tag)
+    }
+}
+
+/**
+ * What to do with an element that is not permitted by the converter options.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownElementPolicy {
+    /// Ignore the element itself but keep converting its children.
+    #[default]
+    Unwrap,
+    /// Ignore the element and everything inside of it.
+    Drop,
+}
+
+/**
+ * What to do with form controls, which have no ADF equivalent.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FormFieldPolicy {
+    /// Keep the text of a control: `textarea` and `button` contents become paragraphs,
+    /// and a text `input` contributes its value (or failing that its placeholder).
+    /// `select` elements are always dropped.
+    #[default]
+    Text,
+    /// Ignore all form controls and everything inside of them.
+    Drop,
+}
+
+/**
+ * The order of the marks on a text node.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MarkOrder {
+    /// The order of the Atlassian editor: link, textColor, strong, em, etc. Unknown marks come last.
+    #[default]
+    Canonical,
+    /// The order the marks were introduced in, from the outermost element inwards.
+    Source,
+}
+
+/**
+ * ADF lists always count up, so `<ol reversed>` cannot be represented as it is.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReversedListPolicy {
+    /// Keep the items in source order, and report a warning.
+    #[default]
+    Warn,
+    /// Put the items in reverse order, so that each keeps its number.
+    Reverse,
+}
+
+/**
+ * ADF numbers the items of a list consecutively from its `order`, so an item cannot set its own number.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListItemValuePolicy {
+    /// A list without a `start` starts at the `value` of its first item.
+    /// The `value` of any later item is ignored.
+    #[default]
+    StartList,
+    /// Ignore the `value` of every item, and report a warning for each.
+    Warn,
+}
+
+/**
+ * What to do with the (deprecated) `summary` attribute of tables, describing the table for screen readers.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TableSummaryPolicy {
+    /// Drop the summary, and report a warning.
+    #[default]
+    Warn,
+    /// Put the summary in a paragraph right before the table.
+    Paragraph,
+}
+
+/**
+ * What to do with the accessibility attributes of table cells (`scope` and `headers`).
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CellAccessibilityPolicy {
+    /// Drop the attributes, and report a warning for each.
+    #[default]
+    Warn,
+    /// Keep the attributes on the cell, as `html:scope` and `html:headers`.
+    Preserve,
+}
+
+/**
+ * What to do with the edit metadata (`datetime` and `cite`) of `<del>` and `<ins>`.
+ * Either way their text keeps its strike or underline mark.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EditMetadataPolicy {
+    /// Drop the attributes, and report a warning for each.
+    #[default]
+    Warn,
+    /// Drop the attributes silently.
+    Ignore,
+}
+
+impl ConverterOptions {
+    /**
+     * Register a hook generating attributes for every element with the given tag name.
+     * Hooks run after the default mapping, and their attributes replace any default
+     * attributes with the same name. For images the attributes apply to the media node.
+     *
+     * Only elements that become ADF nodes carry attributes (headings, paragraphs, lists,
+     * tables, code blocks, images etc). Elements that become text or marks (`a`, `b`, `span`...)
+     * as well as `br` and `hr` do not, so hooks for them never run.
+     * `convert_html_str_to_adf_str_with_warnings` reports any such hook as a warning.
+     */
+    pub fn with_attribute_hook<F>(mut self, tag: &str, hook: F) -> Self
+    where
+        F: Fn(&ElementRef) -> Vec<(String, Value)> + Send + Sync + 'static,
+    {
+        self.attribute_hooks.push(AttributeHook {
+            tag: tag.to_string(),
+            hook: Arc::new(hook),
+        });
+        self
+    }
+
+    /**
+     * Register a transformer deciding how elements are converted, ahead of the built in mapping.
+     * Transformers run in the order they are registered, see [`NodeTransformer`].
+     */
+    pub fn with_node_transformer<T>(mut self, transformer: T) -> Self
+    where
+        T: NodeTransformer + 'static,
+    {
+        self.node_transformers.push(Arc::new(transformer));
+        self
+    }
+
+    /**
+     * Returns true if the given tag name may be converted under these options.
+     */
+    pub fn is_tag_allowed(&self, name: &str) -> bool {
+        match &self.allowed_tags {
+            Some(allowed_tags) => {
+                name == "html"
+                    || name == "body"
+                    || allowed_tags.iter().any(|tag| tag.eq_ignore_ascii_case(name))
+            }
+            None => true,
+        }
+    }
+}
+
+impl Default for ConverterOptions {
+    fn default() -> Self {
+        Self {
+            max_heading_level: 6,
+            capture_heading_ids: false,
+            capture_expanded_state: false,
+            allowed_tags: None,
+            unknown_element_policy: UnknownElementPolicy::Unwrap,
+            editor_wrappers: ["div[contenteditable]:not([contenteditable=false])", "div.ProseMirror"]
+                .iter()
+                .map(|selector| Selector::parse(selector).unwrap())
+                .collect(),
+            panel_classes: vec![],
+            capture_comments: false,
+            capture_direction_overrides: false,
+            strip_line_numbers: false,
+            markdown_inline_code: false,
+            preserve_line_breaks_in_paragraphs: false,
+            muted_small_text: false,
+            italic_abbreviations: false,
+            monospace_code: false,
+            max_text_node_len: None,
+            adf_version: 1,
+            doc_metadata: vec![],
+            max_output_nodes: None,
+            keep_adjacent_rules: false,
+            trim_document: false,
+            split_paragraphs_at_double_breaks: false,
+            nbsp_indentation: false,
+            empty_document_paragraph: true,
+            omit_trailing_empty_cells: false,
+            reversed_list_policy: ReversedListPolicy::Warn,
+            list_item_value_policy: ListItemValuePolicy::StartList,
+            table_summary_policy: TableSummaryPolicy::Warn,
+            cell_accessibility_policy: CellAccessibilityPolicy::Warn,
+            edit_metadata_policy: EditMetadataPolicy::Warn,
+            pretty_json: false,
+            mark_order: MarkOrder::Canonical,
+            form_field_policy: FormFieldPolicy::Text,
+            exclude_aria_hidden: false,
+            exclude_display_none: false,
+            base_url: None,
+            resolve_fragment_links: false,
+            stripped_query_params: vec![],
+            attribute_hooks: vec![],
+            node_transformers: vec![],
+        }
+    }
+}
@@ -3,12 +3,43 @@ use scraper::ElementRef;
 use scraper::Node;
 use std::fmt;
 
+/**
+ * A leaf of our parsed HTML, to be assembled into an ADF document.
+ * The node type comes from ego_tree, which is re-exported as `htmltoadf::ego_tree`.
+ */
 pub struct DocNode<'a> {
+    /// The leaf type, e.g. "text", "img", "br" or "hr".
     pub name: &'a str,
+    /// The text content of the leaf (empty for non-text leaves).
     pub text: String,
+    /// The node within the parsed HTML. Use `node.ancestors()` to walk up to the root.
     pub node: NodeRef<'a, Node>,
 }
 
+impl<'a> DocNode<'a> {
+    /// The names of the elements this leaf is nested in, from its parent up to the root.
+    ///
+    /// ```rust
+    /// use htmltoadf::{extract_leaves, parse_html, ConverterOptions};
+    ///
+    /// let html = parse_html("<ul><li><b>Bold</b></li></ul>".to_string());
+    /// let leaves = extract_leaves(&html, &ConverterOptions::default());
+    /// let ancestors: Vec<&str> = leaves[0].ancestors_named().collect();
+    /// assert_eq!(ancestors, vec!["b", "li", "ul", "html"]);
+    /// ```
+    pub fn ancestors_named(&self) -> impl Iterator<Item = &'a str> {
+        ancestor_names(self.node)
+    }
+}
+
+/**
+ * The names of the elements a node is nested in, from its parent up to the root.
+ */
+pub fn ancestor_names<'a>(node: NodeRef<'a, Node>) -> impl Iterator<Item = &'a str> {
+    node.ancestors()
+        .filter_map(|ancestor| ancestor.value().as_element().map(|element| element.name()))
+}
+
 impl<'a> fmt::Debug for DocNode<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if let Some(element) = ElementRef::wrap(self.node) {
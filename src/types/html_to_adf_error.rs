@@ -0,0 +1,31 @@
+use std::error::Error;
+use std::fmt;
+
+/**
+ * Describes an input that could not be converted at all.
+ * HTML parsing itself never fails (malformed HTML is repaired as a browser would),
+ * so this only reports a conversion that failed unexpectedly, or an output that is too large.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HtmlToAdfError {
+    /// The conversion panicked, with the given message.
+    ConversionFailed(String),
+    /// The converted document has more nodes than the `max_output_nodes` option allows.
+    TooLarge {
+        /// The number of nodes allowed.
+        limit: usize,
+    },
+}
+
+impl fmt::Display for HtmlToAdfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HtmlToAdfError::ConversionFailed(message) => write!(f, "conversion failed: {message}"),
+            HtmlToAdfError::TooLarge { limit } => {
+                write!(f, "the converted document has more than {limit} nodes")
+            }
+        }
+    }
+}
+
+impl Error for HtmlToAdfError {}
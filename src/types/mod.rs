@@ -1,5 +1,10 @@
 pub mod adf_content_type;
 pub mod adf_node;
 pub mod adf_permitted_children;
+pub mod conversion_stats;
+pub mod conversion_warning;
+pub mod converter_options;
 pub mod doc_node;
-pub mod node_list;
\ No newline at end of file
+pub mod html_to_adf_error;
+pub mod node_list;
+pub mod node_transformer;
\ No newline at end of file
@@ -1,17 +1,24 @@
 use ego_tree::NodeId;
 use serde_json::{Map, Value};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use super::adf_node::AdfNode;
 
 pub type NodeHandle = usize;
 
+// Node types that are emitted even without text or children
+static EMPTY_TYPES: [&str; 8] = ["media", "emoji", "date", "rule", "hardBreak", "tableCell", "tableHeader", "embedCard"];
+
 #[derive(Default)]
 #[derive(Debug)]
 pub struct NodeList {
     pub nodes: Vec<AdfNode>,
     pub handles: HashMap<NodeId, NodeHandle>,
     pub count: usize,
+    /// Nodes emitted even though they are empty, as their parent may not be (see `fill_empty`).
+    pub placeholders: HashSet<NodeHandle>,
+    /// Text nodes whose whitespace is kept as it is (within a `<pre>`), rather than trimmed at the edges of their block.
+    pub preformatted: HashSet<NodeHandle>,
 }
 
 /**
@@ -47,6 +54,31 @@ impl NodeList {
         self.handles.retain(|_k, v| v != handle);
     }
 
+    /**
+     * Move the node for a given handle to the end of its parent's children.
+     */
+    pub fn move_to_end(&mut self, handle: NodeHandle) {
+        if let Some(parent_handle) = self.node(handle).map(|node| node.parent) {
+            if let Some(parent_node) = self.node_mut(parent_handle) {
+                parent_node.children.retain(|child| *child != handle);
+                parent_node.children.push(handle);
+            }
+        }
+    }
+
+    /**
+     * Move the node for a given handle to the position right before a sibling in its parent's children.
+     */
+    pub fn move_before(&mut self, handle: NodeHandle, sibling: NodeHandle) {
+        if let Some(parent_handle) = self.node(handle).map(|node| node.parent) {
+            if let Some(parent_node) = self.node_mut(parent_handle) {
+                parent_node.children.retain(|child| *child != handle);
+                let index = parent_node.children.iter().position(|child| *child == sibling).unwrap_or(0);
+                parent_node.children.insert(index, handle);
+            }
+        }
+    }
+
     /**
      * Create and insert a new node into our tree.
      * The node is pushed and associated with a unique node_id.
@@ -121,19 +153,100 @@ impl NodeList {
     }
 
     /**
-     * Convert a NodeList into a JSON document
+     * Nodes without text or children are left out of our JSON document, unless they are of a type
+     * that is complete without (e.g. a rule).
+     */
+    fn is_emitted(&self, handle: NodeHandle) -> bool {
+        self.node(handle).is_some_and(|node| {
+            !node.text.is_empty()
+                || EMPTY_TYPES.contains(&node.node_type.as_str())
+                || self.placeholders.contains(&handle)
+                || !node.children.is_empty()
+        })
+    }
+
+    /**
+     * Returns true if the node for a given handle has any children that are emitted.
+     */
+    pub fn has_content(&self, handle: NodeHandle) -> bool {
+        self.node(handle)
+            .is_some_and(|node| node.children.iter().any(|child| self.is_emitted(*child)))
+    }
+
+    /**
+     * Give every node of the given type without content an empty child of the filler type,
+     * for nodes that are only valid with some content.
+     */
+    pub fn fill_empty(&mut self, node_type: &str, filler_type: &str) {
+        for handle in 1..=self.nodes.len() {
+            if self.nodes[handle - 1].node_type == node_type && !self.has_content(handle) {
+                let filler = self.push_anon(handle, filler_type.to_string(), "".to_string(), &[], vec![]);
+                self.placeholders.insert(filler);
+            }
+        }
+    }
+
+    /**
+     * Move the children of the node for a given handle, from the given index on, into a new node of the
+     * same type and attributes. The new node is placed right after the original one, and its handle returned.
+     */
+    pub fn split_off(&mut self, handle: NodeHandle, index: usize) -> NodeHandle {
+        let node = &mut self.nodes[handle - 1];
+        let new_node = AdfNode {
+            node_type: node.node_type.clone(),
+            text: "".to_string(),
+            attributes: node.attributes.clone(),
+            children: node.children.split_off(index),
+            marks: node.marks.clone(),
+            parent: node.parent,
+        };
+        let new_handle = self.count + 1;
+        new_node.children.iter().for_each(|child| self.nodes[child - 1].parent = new_handle);
+        if let Some(parent_node) = self.node_mut(new_node.parent) {
+            let position = parent_node.children.iter().position(|child| *child == handle).map_or(0, |position| position + 1);
+            parent_node.children.insert(position, new_handle);
+        }
+        self.nodes.push(new_node);
+        self.count += 1;
+        new_handle
+    }
+
+    /**
+     * Remove all but the first node of any run of adjacent sibling nodes of the given type.
+     * Siblings that are not emitted do not interrupt a run.
      */
-    pub fn to_json(&self) -> String {
+    pub fn collapse_adjacent(&mut self, node_type: &str) {
+        for index in 0..self.nodes.len() {
+            let mut previous_matches = false;
+            let children: Vec<NodeHandle> = self.nodes[index]
+                .children
+                .iter()
+                .copied()
+                .filter(|child| {
+                    if !self.is_emitted(*child) {
+                        return true;
+                    }
+                    let matches = self.node(*child).is_some_and(|node| node.node_type == node_type);
+                    let keep = !(matches && previous_matches);
+                    previous_matches = matches;
+                    keep
+                })
+                .collect();
+            self.nodes[index].children = children;
+        }
+    }
+
+    /**
+     * Convert a NodeList into a JSON value, a document of ADF version 1 (see `document_value` for the configured one).
+     */
+    pub fn to_value(&self) -> Value {
         let mut root_node: Map<String, Value> = Map::new();
         root_node.insert(
             "version".to_string(),
             Value::Number(serde_json::Number::from(1)),
         );
         self.insert_node_into_json(1, &mut root_node);
-        if let Ok(result) = serde_json::to_string(&root_node) {
-            return result;
-        }
-        "".to_string()
+        Value::Object(root_node)
     }
 
     /**
@@ -163,22 +276,9 @@ impl NodeList {
                     });
                 }
 
-                let empty_types = vec![
-                    String::from("media"),
-                    String::from("emoji"),
-                    String::from("rule"),
-                    String::from("hardBreak"),
-                    String::from("tableCell")
-                ];
-
                 if !node.children.is_empty() {
                     let mut children = node.children.clone();
-                    children.retain(|child_handle| {
-                        let child_node: &AdfNode = self.node(*child_handle).unwrap();
-                        !(child_node.text.is_empty()
-                            && !empty_types.contains(&child_node.node_type)
-                            && child_node.children.is_empty())
-                    });
+                    children.retain(|child_handle| self.is_emitted(*child_handle));
                     json.insert(
                         "content".to_string(),
                         Value::Array(
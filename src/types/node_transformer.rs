@@ -0,0 +1,50 @@
+use scraper::ElementRef;
+use std::fmt;
+
+use crate::types::adf_content_type::AdfContentType;
+
+/// What a [`NodeTransformer`] decides for an element.
+#[derive(Clone)]
+pub enum TransformResult {
+    /// Leave the element to the next transformer, or failing that the built in mapping.
+    Default,
+    /// Convert the element as the given content type.
+    Replace(AdfContentType),
+}
+
+/// Intercepts the conversion of elements, to implement site specific rules.
+///
+/// Registered transformers (see [`ConverterOptions::with_node_transformer`](crate::ConverterOptions::with_node_transformer))
+/// run in order for every element that contains content, before the built in mapping is consulted.
+/// The first to return [`TransformResult::Replace`] decides the content type of the element.
+/// Elements converted as a whole (`img`, `iframe`, `br`, `hr`, code blocks and figures) are not intercepted.
+///
+/// ```rust
+/// use htmltoadf::{convert_html_str_to_adf_str_with_options, AdfContentType, ConverterOptions, NodeTransformer, TransformResult};
+/// use htmltoadf::scraper::ElementRef;
+///
+/// struct Quotes;
+///
+/// impl NodeTransformer for Quotes {
+///     fn transform(&self, element: ElementRef, _default: Option<AdfContentType>) -> TransformResult {
+///         match element.value().attr("class") {
+///             Some("quote") => TransformResult::Replace(AdfContentType::from_name("blockquote")),
+///             _ => TransformResult::Default,
+///         }
+///     }
+/// }
+///
+/// let options = ConverterOptions::default().with_node_transformer(Quotes);
+/// let converted = convert_html_str_to_adf_str_with_options(r#"<div class="quote">Hi</div>"#.to_string(), &options);
+/// assert!(converted.contains(r#""type":"blockquote""#));
+/// ```
+pub trait NodeTransformer: Send + Sync {
+    /// Decide how the element is converted. `default` is the built in content type of the element, if it has one.
+    fn transform(&self, element: ElementRef, default: Option<AdfContentType>) -> TransformResult;
+}
+
+impl fmt::Debug for dyn NodeTransformer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "NodeTransformer")
+    }
+}
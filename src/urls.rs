@@ -0,0 +1,65 @@
+use url::{ParseError, Url};
+
+use crate::types::converter_options::ConverterOptions;
+
+/**
+ * Rewrite a URL taken from the HTML (a link href, an image or embed src) according to our converter options.
+ * Relative URLs, including protocol relative ones, are resolved against the base URL when one is set.
+ * Fragment-only URLs (`#section`) link within the document, and are only resolved when configured to.
+ * URLs that are already absolute, or cannot be resolved, are left as they are.
+ * Any query parameters configured to be stripped are then removed.
+ */
+pub fn rewrite_url(url: &str, options: &ConverterOptions) -> String {
+    let url = resolve_url(url, options);
+    if options.stripped_query_params.is_empty() {
+        return url;
+    }
+    strip_query_params(&url, &options.stripped_query_params)
+}
+
+fn resolve_url(url: &str, options: &ConverterOptions) -> String {
+    if url.starts_with('#') && !options.resolve_fragment_links {
+        return url.to_string();
+    }
+    match (&options.base_url, Url::parse(url)) {
+        (Some(base_url), Err(ParseError::RelativeUrlWithoutBase)) => base_url
+            .join(url)
+            .map(String::from)
+            .unwrap_or_else(|_| url.to_string()),
+        _ => url.to_string(),
+    }
+}
+
+/**
+ * Remove matching parameters from the query of a (possibly relative) URL, keeping everything else untouched.
+ * The query is dropped altogether once no parameters remain.
+ */
+fn strip_query_params(url: &str, stripped: &[String]) -> String {
+    let (without_fragment, fragment) = match url.find('#') {
+        Some(index) => url.split_at(index),
+        None => (url, ""),
+    };
+    let (path, query) = match without_fragment.split_once('?') {
+        Some((path, query)) => (path, query),
+        None => return url.to_string(),
+    };
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|param| {
+            let name = param.split('=').next().unwrap_or_default();
+            !param.is_empty() && !stripped.iter().any(|pattern| matches_param(pattern, name))
+        })
+        .collect();
+    if kept.is_empty() {
+        format!("{path}{fragment}")
+    } else {
+        format!("{path}?{}{fragment}", kept.join("&"))
+    }
+}
+
+fn matches_param(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
+    }
+}
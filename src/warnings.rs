@@ -0,0 +1,196 @@
+use ego_tree::NodeId;
+use scraper::node::Element;
+use scraper::{Html, Node};
+use std::collections::HashMap;
+
+use crate::adf_builder::{exceeds_node_limit, CELL_ACCESSIBILITY_ATTRIBUTES};
+use crate::extractor::is_excluded;
+use crate::types::adf_content_type::content_type_for_node_type;
+use crate::types::conversion_warning::{ConversionWarning, SourcePosition};
+use crate::types::converter_options::{
+    CellAccessibilityPolicy, ConverterOptions, EditMetadataPolicy, ListItemValuePolicy, ReversedListPolicy,
+    TableSummaryPolicy,
+};
+use crate::types::node_list::NodeList;
+
+/**
+ * Walk the parsed HTML and collect warnings for any content that
+ * is not represented in the converted ADF document.
+ * The source HTML the fragment was parsed from is used to give the position of each warning.
+ */
+pub fn collect_warnings(fragment: &Html, html: &str, options: &ConverterOptions) -> Vec<ConversionWarning> {
+    let mut warnings = ignored_attribute_hooks(options);
+    let offsets = source_offsets(fragment, html);
+    fragment
+        .root_element()
+        .descendants()
+        .filter(|node| !is_excluded(*node, options))
+        .for_each(|node| {
+            let first = warnings.len();
+            if let Node::Element(element) = node.value() {
+                if element.name() == "ol"
+                    && element.attr("reversed").is_some()
+                    && options.reversed_list_policy == ReversedListPolicy::Warn
+                {
+                    warnings.push(ConversionWarning {
+                        element: "ol".to_string(),
+                        message: "reversed numbering is not represented, items keep their source order".to_string(),
+                        position: None,
+                    });
+                }
+                if element.name() == "li"
+                    && element.attr("value").is_some()
+                    && options.list_item_value_policy == ListItemValuePolicy::Warn
+                {
+                    warnings.push(ConversionWarning {
+                        element: "li".to_string(),
+                        message: "explicit item numbering is not represented".to_string(),
+                        position: None,
+                    });
+                }
+                if element.name() == "table" && options.table_summary_policy == TableSummaryPolicy::Warn {
+                    warnings.extend(dropped_attributes(element, &["summary"]));
+                }
+                let is_cell = element.name() == "td" || element.name() == "th";
+                if is_cell && options.cell_accessibility_policy == CellAccessibilityPolicy::Warn {
+                    warnings.extend(dropped_attributes(element, &CELL_ACCESSIBILITY_ATTRIBUTES));
+                }
+                let is_edit = element.name() == "del" || element.name() == "ins";
+                if is_edit && options.edit_metadata_policy == EditMetadataPolicy::Warn {
+                    warnings.extend(dropped_attributes(element, &["datetime", "cite"]));
+                }
+                // ADF has no notion of text direction, the text itself is kept as it is
+                if element.name() == "bdo" && options.capture_direction_overrides {
+                    warnings.push(ConversionWarning {
+                        element: "bdo".to_string(),
+                        message: "text direction override is not represented, text keeps its source order".to_string(),
+                        position: None,
+                    });
+                } else if element.attr("dir").is_some_and(|dir| dir.trim().eq_ignore_ascii_case("rtl")) {
+                    warnings.push(ConversionWarning {
+                        element: element.name().to_string(),
+                        message: "right-to-left text direction is not represented".to_string(),
+                        position: None,
+                    });
+                }
+            }
+            if let Node::Comment(comment) = node.value() {
+                if options.capture_comments {
+                    warnings.push(ConversionWarning {
+                        element: "#comment".to_string(),
+                        message: comment.comment.trim().to_string(),
+                        position: None,
+                    });
+                }
+            }
+            if let Some(offset) = offsets.get(&node.id()) {
+                warnings[first..]
+                    .iter_mut()
+                    .for_each(|warning| warning.position = Some(SourcePosition::from_offset(html, *offset)));
+            }
+        });
+    warnings
+}
+
+/**
+ * The byte offsets in the source HTML at which elements and comments start.
+ *
+ * The parser keeps no source positions, so the n-th <td> of the parsed tree is taken to be the one
+ * started by the n-th <td> tag of the source. Where the parser adds or duplicates elements of a name
+ * (an implied <tbody>, or repaired misnesting) the counts disagree, and elements of that name are left out.
+ */
+fn source_offsets(fragment: &Html, html: &str) -> HashMap<NodeId, usize> {
+    let tags = source_tags(html);
+    let mut nodes: HashMap<String, Vec<NodeId>> = HashMap::new();
+    fragment.root_element().descendants().for_each(|node| match node.value() {
+        Node::Element(element) => nodes.entry(element.name().to_string()).or_default().push(node.id()),
+        Node::Comment(_) => nodes.entry("#comment".to_string()).or_default().push(node.id()),
+        _ => {}
+    });
+    nodes
+        .iter()
+        .filter_map(|(name, ids)| {
+            let offsets = tags.get(name).filter(|offsets| offsets.len() == ids.len())?;
+            Some(ids.iter().copied().zip(offsets.iter().copied()))
+        })
+        .flatten()
+        .collect()
+}
+
+/**
+ * The byte offsets of the start tags (by lower case name) and comments ("#comment") in some HTML.
+ * The content of elements holding raw text, such as scripts, is skipped.
+ */
+fn source_tags(html: &str) -> HashMap<String, Vec<usize>> {
+    static RAW_TEXT: [&str; 4] = ["script", "style", "textarea", "title"];
+    // Lower casing only ASCII keeps every byte offset the same
+    let html = html.to_ascii_lowercase();
+    let mut tags: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut index = 0;
+    while let Some(found) = html[index..].find('<') {
+        let start = index + found;
+        let rest = &html[start + 1..];
+        index = start + 1;
+        if rest.starts_with("!--") {
+            tags.entry("#comment".to_string()).or_default().push(start);
+            index = rest.find("-->").map_or(html.len(), |end| index + end + 3);
+            continue;
+        }
+        if !rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            continue;
+        }
+        let name: String = rest.chars().take_while(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+        if RAW_TEXT.contains(&name.as_str()) {
+            index = rest.find(&format!("</{name}")).map_or(html.len(), |end| index + end);
+        }
+        tags.entry(name).or_default().push(start);
+    }
+    tags
+}
+
+/**
+ * A warning for each of the given attributes present on an element, which are not converted.
+ */
+fn dropped_attributes(element: &Element, names: &[&str]) -> Vec<ConversionWarning> {
+    names
+        .iter()
+        .filter_map(|name| {
+            element.attr(name).map(|value| ConversionWarning {
+                element: element.name().to_string(),
+                message: format!("{name}=\"{value}\" is not represented"),
+                position: None,
+            })
+        })
+        .collect()
+}
+
+/**
+ * A warning for a document that was cut short, having reached the `max_output_nodes` limit.
+ */
+pub fn truncation_warning(node_list: &NodeList, options: &ConverterOptions) -> Option<ConversionWarning> {
+    let limit = options.max_output_nodes.filter(|_| exceeds_node_limit(node_list, options))?;
+    Some(ConversionWarning {
+        element: "#document".to_string(),
+        message: format!("document truncated at {limit} nodes"),
+        position: None,
+    })
+}
+
+/**
+ * Attribute hooks registered for tags that never carry node attributes.
+ */
+fn ignored_attribute_hooks(options: &ConverterOptions) -> Vec<ConversionWarning> {
+    options
+        .attribute_hooks
+        .iter()
+        .filter(|hook| {
+            let typename = &content_type_for_node_type(&hook.tag.to_ascii_lowercase()).typename;
+            ["text", "hardBreak", "rule"].contains(&typename.as_str())
+        })
+        .map(|hook| ConversionWarning {
+            element: hook.tag.to_string(),
+            message: "attribute hook ignored, this element carries no attributes".to_string(),
+            position: None,
+        })
+        .collect()
+}